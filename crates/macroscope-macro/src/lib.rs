@@ -1,3 +1,5 @@
+mod syntax;
+
 use derive_syn_parse::Parse;
 use macroscope_utils::find_macroscope;
 use proc_macro::TokenStream;
@@ -5,13 +7,14 @@ use proc_macro2::Span;
 use proc_macro_roids::DeriveInputExt;
 use quote::quote;
 use syn::{
-    parse::ParseStream, parse_macro_input, parse_quote, punctuated::Punctuated, DeriveInput, Ident,
-    LitStr, Path, Token,
+    ext::IdentExt, parse::ParseStream, parse_macro_input, parse_quote, punctuated::Punctuated,
+    DeriveInput, FnArg, Ident, ItemFn, LitStr, Path, Token,
 };
 
 #[derive(Debug, Parse)]
 struct UsingArgs {
     crate_name: CrateName,
+    #[allow(dead_code)]
     #[peek(Token![|])]
     extra: Option<UsingArgsExtra>,
 }
@@ -44,6 +47,7 @@ fn is_path(token: ParseStream) -> bool {
     token.peek(syn::Token![::]) || token.peek(Ident)
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Parse)]
 struct UsingArgsExtra {
     pipe: Token![|],
@@ -125,6 +129,459 @@ pub fn data(_args: TokenStream, item: TokenStream) -> TokenStream {
     TokenStream::from(quote! { #ast })
 }
 
+/// Turns `fn name(args: Args, item: Item) -> MacroResult<Tokens>` into a
+/// `#[proc_macro_attribute]` whose inputs are parsed before the body runs.
+///
+/// Inputs are parsed with their `Parse` impl, which can only fail with a
+/// `syn::Error`. For a `FromMeta` type, that flattens its diagnostics and
+/// loses spanned notes, like where a duplicate argument was first given. To
+/// keep them, take the arguments as `Tokens` and call
+/// `FromMeta::from_tokens` in the body.
+#[proc_macro_attribute]
+pub fn attribute_macro(args: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+
+    entry_point(
+        find_macroscope(),
+        quote!(#[proc_macro_attribute]),
+        args.into(),
+        func,
+        2,
+    )
+    .into()
+}
+
+/// Turns `fn name(input: Input) -> MacroResult<Tokens>` into a
+/// `#[proc_macro_derive(..)]`. The arguments are passed through unchanged.
+#[proc_macro_attribute]
+pub fn derive_macro(args: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+    let args = proc_macro2::TokenStream::from(args);
+
+    entry_point(
+        find_macroscope(),
+        quote!(#[proc_macro_derive(#args)]),
+        quote!(),
+        func,
+        1,
+    )
+    .into()
+}
+
+/// Turns `fn name(input: Input) -> MacroResult<Tokens>` into a
+/// function-like `#[proc_macro]`.
+#[proc_macro_attribute]
+pub fn function_macro(args: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+
+    entry_point(
+        find_macroscope(),
+        quote!(#[proc_macro]),
+        args.into(),
+        func,
+        1,
+    )
+    .into()
+}
+
+fn entry_point(
+    macroscope: proc_macro2::TokenStream,
+    kind: proc_macro2::TokenStream,
+    args: proc_macro2::TokenStream,
+    mut func: ItemFn,
+    arity: usize,
+) -> proc_macro2::TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new_spanned(args, "this attribute takes no arguments")
+            .to_compile_error();
+    }
+
+    let sig = &func.sig;
+
+    if sig.inputs.len() != arity {
+        let message = match arity {
+            1 => "expected exactly one parameter".to_string(),
+            n => format!("expected exactly {} parameters", n),
+        };
+        return syn::Error::new_spanned(&sig.inputs, message).to_compile_error();
+    }
+
+    if let Some(FnArg::Receiver(receiver)) = sig.inputs.first() {
+        return syn::Error::new_spanned(receiver, "entry points cannot take `self`")
+            .to_compile_error();
+    }
+
+    if let syn::ReturnType::Default = sig.output {
+        return syn::Error::new_spanned(sig, "entry points must return a `MacroResult`")
+            .to_compile_error();
+    }
+
+    let name = sig.ident.clone();
+    let output = &sig.output;
+
+    let streams: Vec<Ident> = (0..arity)
+        .map(|i| Ident::new(&format!("input{}", i), Span::mixed_site()))
+        .collect();
+
+    // Doc comments document the proc macro itself, so they move to the shim.
+    let (docs, attrs) = func
+        .attrs
+        .drain(..)
+        .partition::<Vec<_>, _>(|attr| attr.path.is_ident("doc"));
+    func.attrs = attrs;
+    func.vis = syn::Visibility::Inherited;
+
+    quote! {
+        #(#docs)*
+        #kind
+        pub fn #name(#(#streams: ::proc_macro::TokenStream),*) -> ::proc_macro::TokenStream {
+            #func
+
+            #macroscope::entry_point(|| #output {
+                #name(#(
+                    #macroscope::parse_tokens!(#macroscope::Tokens::from(#streams))?
+                ),*)
+            })
+        }
+    }
+}
+
+/// Generates `FromMeta` and `Parse` for a struct of named attribute
+/// arguments. See `macroscope::derive_parse::meta`.
+#[proc_macro_derive(FromMeta, attributes(meta))]
+pub fn from_meta(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match derive_from_meta(input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn derive_from_meta(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "FromMeta can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let macroscope = find_macroscope();
+    let meta = quote!(#macroscope::derive_parse::meta);
+    let syn = quote!(#macroscope::tools::syn);
+    let span = quote!(#macroscope::tools::proc_macro2::Span);
+    let error = quote!(#macroscope::error::MacroError);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let span_var = Ident::new("span", Span::mixed_site());
+    let args_var = Ident::new("args", Span::mixed_site());
+    let fields_var = Ident::new("fields", Span::mixed_site());
+
+    let mut keys = vec![];
+    let mut reads = vec![];
+    let mut inits = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        let ident = field.ident.as_ref().expect("named fields have names");
+        let ty = &field.ty;
+        let options = FieldOptions::from_attrs(&field.attrs)?;
+
+        let key = match options.rename {
+            Some(rename) => rename.value(),
+            None => ident.unraw().to_string(),
+        };
+        let var = Ident::new(&format!("field{}", i), Span::mixed_site());
+
+        let read = match options.default {
+            Some(FieldDefault::Trait) => quote! {
+                #fields_var.or_else::<#ty>(#key, ::std::default::Default::default)
+            },
+            Some(FieldDefault::Expr(expr)) => quote! {
+                #fields_var.or_else::<#ty>(#key, || #expr)
+            },
+            None => quote!(#fields_var.required::<#ty>(#key)),
+        };
+
+        reads.push(quote!(let #var = #read;));
+        inits.push(quote!(#ident: #var.unwrap()));
+        keys.push(key);
+    }
+
+    Ok(quote! {
+        impl #impl_generics #meta::FromMeta for #name #ty_generics #where_clause {
+            fn from_list(
+                #span_var: #span,
+                #args_var: &#meta::MetaArgs,
+            ) -> ::std::result::Result<Self, #error> {
+                let mut #fields_var = #meta::MetaFields::new(#span_var, #args_var, &[#(#keys),*]);
+                #(#reads)*
+                #fields_var.finish()?;
+
+                ::std::result::Result::Ok(#name { #(#inits),* })
+            }
+        }
+
+        impl #impl_generics #syn::parse::Parse for #name #ty_generics #where_clause {
+            fn parse(input: #syn::parse::ParseStream) -> #syn::Result<Self> {
+                let #span_var = input.span();
+                let #args_var: #meta::MetaArgs = input.parse()?;
+
+                <Self as #meta::FromMeta>::from_list(#span_var, &#args_var)
+                    .map_err(::std::convert::From::from)
+            }
+        }
+    })
+}
+
+/// Generates `Parse`, `ToTokens`, `Validate` and `HasParts` for a grammar
+/// written as a struct or enum of derive_parse combinators.
+#[proc_macro_derive(Syntax, attributes(syntax))]
+pub fn syntax(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match syntax::derive_syntax(input, find_macroscope()) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    default: Option<FieldDefault>,
+    rename: Option<LitStr>,
+}
+
+enum FieldDefault {
+    Trait,
+    Expr(Box<syn::Expr>),
+}
+
+impl FieldOptions {
+    fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<FieldOptions> {
+        let mut options = FieldOptions::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("meta")) {
+            attr.parse_args_with(|input: ParseStream| loop {
+                let key: Ident = input.parse()?;
+
+                if key == "default" {
+                    options.default = if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        Some(FieldDefault::Expr(input.parse()?))
+                    } else {
+                        Some(FieldDefault::Trait)
+                    };
+                } else if key == "rename" {
+                    input.parse::<Token![=]>()?;
+                    options.rename = Some(input.parse()?);
+                } else {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected `default`, `default = ...` or `rename = \"...\"`",
+                    ));
+                }
+
+                if input.is_empty() {
+                    return Ok(());
+                }
+                input.parse::<Token![,]>()?;
+                if input.is_empty() {
+                    return Ok(());
+                }
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
 fn normalize(name: &str) -> String {
     name.replace("-", "_")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(
+        kind: proc_macro2::TokenStream,
+        args: proc_macro2::TokenStream,
+        func: proc_macro2::TokenStream,
+        arity: usize,
+    ) -> String {
+        let func = syn::parse2(func).unwrap();
+
+        entry_point(quote!(::macroscope), kind, args, func, arity).to_string()
+    }
+
+    fn assert_fails(expansion: String, message: &str) {
+        let expected = quote!(compile_error! { #message }).to_string();
+        assert_eq!(expansion, expected);
+    }
+
+    #[test]
+    fn expands_attribute_macros() {
+        let expansion = expand(
+            quote!(#[proc_macro_attribute]),
+            quote!(),
+            quote! {
+                /// Docs.
+                #[inline]
+                pub fn name(args: Args, item: Item) -> MacroResult<Tokens> {
+                    body()
+                }
+            },
+            2,
+        );
+
+        let input0 = Ident::new("input0", Span::mixed_site());
+        let input1 = Ident::new("input1", Span::mixed_site());
+        let expected = quote! {
+            /// Docs.
+            #[proc_macro_attribute]
+            pub fn name(#input0: ::proc_macro::TokenStream, #input1: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+                #[inline]
+                fn name(args: Args, item: Item) -> MacroResult<Tokens> {
+                    body()
+                }
+
+                ::macroscope::entry_point(|| -> MacroResult<Tokens> {
+                    name(
+                        ::macroscope::parse_tokens!(::macroscope::Tokens::from(#input0))?,
+                        ::macroscope::parse_tokens!(::macroscope::Tokens::from(#input1))?
+                    )
+                })
+            }
+        };
+
+        assert_eq!(expansion, expected.to_string());
+    }
+
+    #[test]
+    fn expands_derive_and_function_macros() {
+        let func = quote! {
+            fn name(input: Input) -> MacroResult<Tokens> {
+                body()
+            }
+        };
+
+        let expected = |kind: proc_macro2::TokenStream| {
+            let input0 = Ident::new("input0", Span::mixed_site());
+
+            quote! {
+                #kind
+                pub fn name(#input0: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+                    fn name(input: Input) -> MacroResult<Tokens> {
+                        body()
+                    }
+
+                    ::macroscope::entry_point(|| -> MacroResult<Tokens> {
+                        name(::macroscope::parse_tokens!(::macroscope::Tokens::from(#input0))?)
+                    })
+                }
+            }
+            .to_string()
+        };
+
+        let derive = quote!(#[proc_macro_derive(Name, attributes(name))]);
+        assert_eq!(
+            expand(derive.clone(), quote!(), func.clone(), 1),
+            expected(derive)
+        );
+
+        let function = quote!(#[proc_macro]);
+        assert_eq!(
+            expand(function.clone(), quote!(), func, 1),
+            expected(function)
+        );
+    }
+
+    #[test]
+    fn rejects_arguments() {
+        assert_fails(
+            expand(
+                quote!(#[proc_macro]),
+                quote!(unexpected),
+                quote!(
+                    fn name(input: Input) -> MacroResult<Tokens> {}
+                ),
+                1,
+            ),
+            "this attribute takes no arguments",
+        );
+    }
+
+    #[test]
+    fn rejects_the_wrong_number_of_parameters() {
+        assert_fails(
+            expand(
+                quote!(#[proc_macro_attribute]),
+                quote!(),
+                quote!(
+                    fn name(item: Item) -> MacroResult<Tokens> {}
+                ),
+                2,
+            ),
+            "expected exactly 2 parameters",
+        );
+
+        assert_fails(
+            expand(
+                quote!(#[proc_macro_derive(Name)]),
+                quote!(),
+                quote!(
+                    fn name(input: Input, extra: Extra) -> MacroResult<Tokens> {}
+                ),
+                1,
+            ),
+            "expected exactly one parameter",
+        );
+
+        assert_fails(
+            expand(
+                quote!(#[proc_macro]),
+                quote!(),
+                quote!(
+                    fn name() -> MacroResult<Tokens> {}
+                ),
+                1,
+            ),
+            "expected exactly one parameter",
+        );
+    }
+
+    #[test]
+    fn rejects_receivers_and_missing_return_types() {
+        assert_fails(
+            expand(
+                quote!(#[proc_macro]),
+                quote!(),
+                quote!(
+                    fn name(&self) -> MacroResult<Tokens> {}
+                ),
+                1,
+            ),
+            "entry points cannot take `self`",
+        );
+
+        assert_fails(
+            expand(
+                quote!(#[proc_macro]),
+                quote!(),
+                quote!(
+                    fn name(input: Input) {}
+                ),
+                1,
+            ),
+            "entry points must return a `MacroResult`",
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident, Index, Member, Type};
+
+/// `Parse`, `ToTokens`, `Validate` and (for structs) `HasParts` for a grammar
+/// written as a struct or enum of parseable fields.
+///
+/// Structs parse their fields in order. Enums try each variant in order and
+/// take the first one that parses, like `OrderedChoice`, reporting every
+/// alternative when none does.
+pub(crate) fn derive_syntax(
+    input: DeriveInput,
+    macroscope: TokenStream,
+) -> syn::Result<TokenStream> {
+    let no_validate = no_validate(&input)?;
+    let paths = Paths::new(macroscope);
+
+    let mut tokens = match &input.data {
+        Data::Struct(data) => derive_struct(&input, &data.fields, &paths)?,
+        Data::Enum(data) => derive_enum(&input, data, &paths)?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "Syntax can't be derived for unions",
+            ))
+        }
+    };
+
+    if !no_validate {
+        tokens.extend(derive_validate(&input, &paths)?);
+    }
+
+    Ok(tokens)
+}
+
+struct Paths {
+    syn: TokenStream,
+    to_tokens: TokenStream,
+    validate: TokenStream,
+    has_parts: TokenStream,
+    choice: TokenStream,
+    lookahead: TokenStream,
+    token_stream: TokenStream,
+}
+
+impl Paths {
+    fn new(macroscope: TokenStream) -> Paths {
+        Paths {
+            syn: quote!(#macroscope::tools::syn),
+            to_tokens: quote!(#macroscope::tools::quote::ToTokens),
+            validate: quote!(#macroscope::derive_parse::validate::Validate),
+            has_parts: quote!(#macroscope::derive_parse::wrapper::HasParts),
+            choice: quote!(#macroscope::derive_parse::choice),
+            lookahead: quote!(#macroscope::derive_parse::lookahead),
+            token_stream: quote!(#macroscope::tools::proc_macro2::TokenStream),
+        }
+    }
+}
+
+/// `#[syntax(no_validate)]` skips the `Validate` impl, for grammars whose
+/// first field can't be peeked, like an `Optional`.
+fn no_validate(input: &DeriveInput) -> syn::Result<bool> {
+    let mut no_validate = false;
+
+    for attr in input.attrs.iter().filter(|a| a.path.is_ident("syntax")) {
+        let option: Ident = attr.parse_args()?;
+
+        if option == "no_validate" {
+            no_validate = true;
+        } else {
+            return Err(syn::Error::new(option.span(), "expected `no_validate`"));
+        }
+    }
+
+    Ok(no_validate)
+}
+
+/// The members of `fields` with a binding for each, in order.
+fn bindings(fields: &Fields) -> Vec<(Member, Ident, &Type)> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i)),
+            };
+            let binding = Ident::new(&format!("field{}", i), Span::mixed_site());
+
+            (member, binding, &field.ty)
+        })
+        .collect()
+}
+
+/// The bounds needed on field types for a generic grammar.
+fn where_clause(input: &DeriveInput, bound: &TokenStream) -> Option<syn::WhereClause> {
+    if input.generics.params.is_empty() {
+        return input.generics.where_clause.clone();
+    }
+
+    let mut where_clause = input
+        .generics
+        .where_clause
+        .clone()
+        .unwrap_or_else(|| syn::parse_quote!(where));
+
+    for ty in field_types(input) {
+        where_clause.predicates.push(syn::parse_quote!(#ty: #bound));
+    }
+
+    Some(where_clause)
+}
+
+fn field_types(input: &DeriveInput) -> Vec<&Type> {
+    match &input.data {
+        Data::Struct(data) => data.fields.iter().map(|field| &field.ty).collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+            .collect(),
+        Data::Union(_) => vec![],
+    }
+}
+
+fn derive_struct(input: &DeriveInput, fields: &Fields, paths: &Paths) -> syn::Result<TokenStream> {
+    let Paths {
+        syn,
+        to_tokens,
+        has_parts,
+        lookahead,
+        token_stream,
+        ..
+    } = paths;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let bindings = bindings(fields);
+    let sequence = format_ident!("sequence", span = Span::mixed_site());
+
+    let parse_bound = quote!(#syn::parse::Parse);
+    let parse_where = where_clause(input, &parse_bound);
+    let tokens_where = where_clause(input, to_tokens);
+
+    let members: Vec<_> = bindings.iter().map(|(member, ..)| member).collect();
+    let vars: Vec<_> = bindings.iter().map(|(_, var, _)| var).collect();
+    let types: Vec<_> = bindings.iter().map(|(.., ty)| ty).collect();
+
+    // `HasParts` borrows for `'a`, so every field type has to outlive it.
+    let lifetime = syn::Lifetime::new("'a", Span::call_site());
+    let mut parts_generics = input.generics.clone();
+    parts_generics
+        .params
+        .insert(0, syn::parse_quote!(#lifetime));
+    let (parts_impl_generics, ..) = parts_generics.split_for_impl();
+    let parts_where = where_clause(input, &quote!(#lifetime));
+
+    Ok(quote! {
+        impl #impl_generics #syn::parse::Parse for #name #ty_generics #parse_where {
+            fn parse(input: #syn::parse::ParseStream) -> #syn::Result<Self> {
+                let mut #sequence = #lookahead::Sequence::new(input);
+                #(let #vars = #sequence.parse()?;)*
+
+                ::std::result::Result::Ok(#name { #(#members: #vars),* })
+            }
+        }
+
+        impl #impl_generics #to_tokens for #name #ty_generics #tokens_where {
+            fn to_tokens(&self, tokens: &mut #token_stream) {
+                #(#to_tokens::to_tokens(&self.#members, tokens);)*
+            }
+        }
+
+        impl #parts_impl_generics #has_parts<#lifetime> for #name #ty_generics #parts_where {
+            type IntoParts = (#(#types,)*);
+            type AsParts = (#(&#lifetime #types,)*);
+
+            fn into_parts(self) -> Self::IntoParts {
+                (#(self.#members,)*)
+            }
+
+            fn as_parts(&#lifetime self) -> Self::AsParts {
+                (#(&self.#members,)*)
+            }
+        }
+    })
+}
+
+fn derive_enum(
+    input: &DeriveInput,
+    data: &syn::DataEnum,
+    paths: &Paths,
+) -> syn::Result<TokenStream> {
+    let Paths {
+        syn,
+        to_tokens,
+        choice,
+        token_stream,
+        ..
+    } = paths;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+
+    let parse_where = where_clause(input, &quote!(#syn::parse::Parse));
+    let tokens_where = where_clause(input, to_tokens);
+
+    let variants: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+            let (members, vars): (Vec<_>, Vec<_>) = bindings(&variant.fields)
+                .into_iter()
+                .map(|(member, var, _)| (member, var))
+                .unzip();
+
+            (ident, members, vars)
+        })
+        .collect();
+
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "Syntax can't be derived for enums without variants",
+        ));
+    }
+
+    let alternatives = format_ident!("alternatives", span = Span::mixed_site());
+
+    let attempts = variants.iter().map(|(ident, members, vars)| {
+        quote! {
+            let attempt = #alternatives.attempt(|input| -> #syn::Result<Self> {
+                #(let #vars = input.parse()?;)*
+                ::std::result::Result::Ok(#name::#ident { #(#members: #vars),* })
+            });
+
+            if let ::std::option::Option::Some(parsed) = attempt {
+                return ::std::result::Result::Ok(parsed);
+            }
+        }
+    });
+
+    let arms = variants.iter().map(|(ident, members, vars)| {
+        quote! {
+            #name::#ident { #(#members: #vars),* } => {
+                #(#to_tokens::to_tokens(#vars, tokens);)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #syn::parse::Parse for #name #ty_generics #parse_where {
+            fn parse(input: #syn::parse::ParseStream) -> #syn::Result<Self> {
+                let mut #alternatives = #choice::Alternatives::new(input);
+
+                #(#attempts)*
+
+                ::std::result::Result::Err(#alternatives.into_error())
+            }
+        }
+
+        impl #impl_generics #to_tokens for #name #ty_generics #tokens_where {
+            fn to_tokens(&self, tokens: &mut #token_stream) {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/// A struct validates if its first field does; an enum if any variant does.
+/// Empty structs and variants always validate.
+fn derive_validate(input: &DeriveInput, paths: &Paths) -> syn::Result<TokenStream> {
+    let Paths { syn, validate, .. } = paths;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let first_types: Vec<&Type> = match &input.data {
+        Data::Struct(data) => data.fields.iter().take(1).map(|f| &f.ty).collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .filter_map(|variant| variant.fields.iter().next().map(|f| &f.ty))
+            .collect(),
+        Data::Union(_) => vec![],
+    };
+
+    if let Some((ty, combinator)) = first_types
+        .iter()
+        .find_map(|ty| unpeekable_combinator(ty).map(|combinator| (ty, combinator)))
+    {
+        return Err(syn::Error::new_spanned(
+            ty,
+            format!(
+                "`{}` can't be peeked, so `Validate` can't be derived for `{}`; \
+                 add `#[syntax(no_validate)]` to skip it",
+                combinator, name
+            ),
+        ));
+    }
+
+    let has_empty = match &input.data {
+        Data::Struct(data) => data.fields.is_empty(),
+        Data::Enum(data) => data.variants.iter().any(|v| v.fields.is_empty()),
+        Data::Union(_) => false,
+    };
+
+    let mut where_clause = where_clause.cloned();
+    if !input.generics.params.is_empty() {
+        let predicates = &mut where_clause
+            .get_or_insert_with(|| syn::parse_quote!(where))
+            .predicates;
+
+        for ty in &first_types {
+            predicates.push(syn::parse_quote!(#ty: #validate));
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #validate for #name #ty_generics #where_clause {
+            fn validate(stream: &#syn::parse::ParseStream) -> bool {
+                #has_empty #(|| <#first_types as #validate>::validate(stream))*
+            }
+
+            fn expected() -> ::std::vec::Vec<::std::string::String> {
+                let mut expected = ::std::vec::Vec::new();
+                #(expected.extend(<#first_types as #validate>::expected());)*
+                expected
+            }
+        }
+    })
+}
+
+/// The name of `ty` if it's one of the combinators that have no `Validate`
+/// impl, because what they start with can't be told from a single peek. This
+/// has to be kept in sync with the `Validate` impls in `derive_parse`; anything
+/// it misses fails later, as an unsatisfied `Validate` bound.
+fn unpeekable_combinator(ty: &Type) -> Option<&Ident> {
+    const UNPEEKABLE: &[&str] = &[
+        "Option",
+        "Optional",
+        "OptionalEnd",
+        "Delimited",
+        "Braced",
+        "Bracketed",
+        "Parenthesized",
+        "Piped",
+        "Angled",
+        "Separated",
+        "SeparatedNoTrailing",
+        "SeparatedRequireTrailing",
+        "Until",
+    ];
+
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .map(|segment| &segment.ident)
+            .filter(|ident| UNPEEKABLE.iter().any(|name| *ident == name)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combinator(ty: &str) -> Option<String> {
+        let ty: Type = syn::parse_str(ty).unwrap();
+        unpeekable_combinator(&ty).map(|ident| ident.to_string())
+    }
+
+    #[test]
+    fn finds_every_separated_variant_without_validate() {
+        for ty in &[
+            "Separated<Ident, Comma>",
+            "SeparatedNoTrailing<Ident, Comma>",
+            "derive_parse::SeparatedRequireTrailing<Ident, Comma>",
+        ] {
+            assert!(combinator(ty).is_some(), "{} should be unpeekable", ty);
+        }
+
+        assert_eq!(combinator("SeparatedNonEmpty<Ident, Comma>"), None);
+        assert_eq!(combinator("SeparatedInline<Ident, Comma>"), None);
+        assert_eq!(combinator("Terminated<Ident, Comma>"), None);
+    }
+}
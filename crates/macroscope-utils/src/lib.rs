@@ -32,7 +32,7 @@ pub fn find_macroscope() -> proc_macro2::TokenStream {
 
 pub fn find_crate(first: LitStr /*, rest: impl Iterator<Item = LitStr> */) -> FoundCrate {
     match crate_name(&first.value()) {
-        Ok(c) => return c,
+        Ok(c) => c,
         Err(err) => panic!("{}", err),
     }
 
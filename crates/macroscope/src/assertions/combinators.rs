@@ -0,0 +1,211 @@
+use std::fmt::Debug;
+
+use crate::{
+    assertions::traits::{assert_all, AssertResult, Assertion},
+    impl_prelude::*,
+};
+
+/// Passes if every assertion passes. Failures are reported together.
+#[derive(Debug)]
+pub struct All<N>
+where
+    N: AstNode + 'static,
+{
+    assertions: Vec<Assertion<N>>,
+}
+
+impl<N> All<N>
+where
+    N: AstNode + 'static,
+{
+    pub fn new() -> All<N> {
+        All { assertions: vec![] }
+    }
+
+    pub fn and(mut self, assertion: impl AstAssertion<N>) -> Self {
+        self.assertions.push(assertion.assertion());
+        self
+    }
+}
+
+impl<N> Default for All<N>
+where
+    N: AstNode + 'static,
+{
+    fn default() -> All<N> {
+        All::new()
+    }
+}
+
+impl<N> AstAssertion<N> for All<N>
+where
+    N: AstNode + Debug + 'static,
+{
+    fn expectation(&self) -> String {
+        join_expectations(&self.assertions, "and")
+    }
+
+    fn assert(&self, node: &N) -> AssertResult {
+        assert_all(&self.assertions, node)
+    }
+}
+
+/// Passes if at least one of the assertions passes.
+#[derive(Debug)]
+pub struct Any<N>
+where
+    N: AstNode + 'static,
+{
+    assertions: Vec<Assertion<N>>,
+}
+
+impl<N> Any<N>
+where
+    N: AstNode + 'static,
+{
+    pub fn new() -> Any<N> {
+        Any { assertions: vec![] }
+    }
+
+    pub fn or(mut self, assertion: impl AstAssertion<N>) -> Self {
+        self.assertions.push(assertion.assertion());
+        self
+    }
+}
+
+impl<N> Default for Any<N>
+where
+    N: AstNode + 'static,
+{
+    fn default() -> Any<N> {
+        Any::new()
+    }
+}
+
+impl<N> AstAssertion<N> for Any<N>
+where
+    N: AstNode + Debug + 'static,
+{
+    fn expectation(&self) -> String {
+        join_expectations(&self.assertions, "or")
+    }
+
+    fn assert(&self, node: &N) -> AssertResult {
+        let mut failures = vec![];
+
+        for assertion in &self.assertions {
+            match assertion.assert(node) {
+                Ok(()) => return Ok(()),
+                Err(failure) => failures.push(failure),
+            }
+        }
+
+        let error = MacroError::expected_property(node.description())
+            .to_be(self.expectation())
+            .but_was(node);
+
+        Err(failures
+            .into_iter()
+            .fold(error, |error, failure| error.info(failure.to_string()))
+            .into())
+    }
+}
+
+/// Passes if the assertion fails.
+#[derive(Debug)]
+pub struct Not<N>
+where
+    N: AstNode + 'static,
+{
+    assertion: Assertion<N>,
+}
+
+impl<N> Not<N>
+where
+    N: AstNode + 'static,
+{
+    pub fn new(assertion: impl AstAssertion<N>) -> Not<N> {
+        Not {
+            assertion: assertion.assertion(),
+        }
+    }
+}
+
+impl<N> AstAssertion<N> for Not<N>
+where
+    N: AstNode + Debug + 'static,
+{
+    fn expectation(&self) -> String {
+        format!("not {}", self.assertion.expectation())
+    }
+
+    fn assert(&self, node: &N) -> AssertResult {
+        match self.assertion.assert(node) {
+            Ok(()) => Err(MacroError::expected_property(node.description())
+                .to_not_be(self.assertion.expectation())
+                .but_was(node)
+                .into()),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// An assertion from a closure, for checks that don't deserve their own type.
+/// The description is what the closure expects, e.g. "returning a `Result`".
+pub struct Predicate<N> {
+    description: String,
+    predicate: Box<dyn Fn(&N) -> bool>,
+}
+
+impl<N> Predicate<N> {
+    pub fn new(description: impl Into<String>, predicate: impl Fn(&N) -> bool + 'static) -> Self {
+        Predicate {
+            description: description.into(),
+            predicate: Box::new(predicate),
+        }
+    }
+}
+
+impl<N> Debug for Predicate<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Predicate")
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+impl<N> AstAssertion<N> for Predicate<N>
+where
+    N: AstNode + 'static,
+{
+    fn expectation(&self) -> String {
+        self.description.clone()
+    }
+
+    fn assert(&self, node: &N) -> AssertResult {
+        if (self.predicate)(node) {
+            Ok(())
+        } else {
+            Err(MacroError::expected_property(node.description())
+                .to_be(self.description.clone())
+                .but_was(node)
+                .into())
+        }
+    }
+}
+
+fn join_expectations<N>(assertions: &[Assertion<N>], conjunction: &str) -> String
+where
+    N: AstNode + Debug + 'static,
+{
+    let expectations: Vec<String> = assertions
+        .iter()
+        .map(|assertion| assertion.expectation())
+        .collect();
+
+    match expectations.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} {} {}", rest.join(", "), conjunction, last),
+    }
+}
@@ -0,0 +1,172 @@
+/// Builds an `Assertion<Function>` from a comma-separated list of clauses,
+/// so an attribute macro can state its input requirements in one line:
+///
+/// ```ignore
+/// let assertion = assert_fn!(async, pub, params == 2, returns Result<_, _>, name ~ "test_*");
+/// ```
+///
+/// Every clause is checked, and each failed clause is reported by name. An
+/// unknown clause is a compile error.
+///
+/// | Clause | Requires |
+/// |--------|----------|
+/// | `async`, `!async` | the function to be (or not be) async |
+/// | `!const`, `!unsafe` | the function to not be const or unsafe |
+/// | `pub`, `pub(crate)`, `private` | the function's visibility |
+/// | `params == N`, `params >= N`, `params <= N` | the number of parameters, excluding `self` |
+/// | `!generic` | no generic parameters |
+/// | `!self`, `&self`, `&mut self` | the receiver |
+/// | `returns Type` | the return type, where `_` matches any type |
+/// | `name == "name"`, `name ~ "glob*"` | the function name |
+/// | `extern`, `extern "abi"` | an extern ABI |
+#[macro_export]
+macro_rules! assert_fn {
+    (@clauses $all:ident; ) => {
+        $crate::assertions::traits::AstAssertion::<$crate::ast::Function>::assertion($all)
+    };
+
+    (@clauses $all:ident; async $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["async"] $crate::assertions::function::AssertAsync; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; !async $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["!async"] $crate::assertions::combinators::Not::<$crate::ast::Function>::new(
+            $crate::assertions::function::AssertAsync
+        ); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; !const $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["!const"] $crate::assertions::function::AssertNotConst; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; !unsafe $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["!unsafe"] $crate::assertions::function::AssertNotUnsafe; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; pub(crate) $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["pub(crate)"] $crate::assertions::function::AssertVisibility::Crate; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; pub $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["pub"] $crate::assertions::function::AssertVisibility::Public; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; private $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["private"] $crate::assertions::function::AssertVisibility::Private; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; params == $count:expr $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("params == ", stringify!($count))] $crate::assertions::function::AssertParamCount::exactly($count); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; params >= $count:expr $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("params >= ", stringify!($count))] $crate::assertions::function::AssertParamCount::at_least($count); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; params <= $count:expr $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("params <= ", stringify!($count))] $crate::assertions::function::AssertParamCount::at_most($count); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; !generic $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["!generic"] $crate::assertions::function::AssertNoGenerics; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; !self $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["!self"] $crate::assertions::function::AssertNoSelf; $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; &mut self $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["&mut self"] $crate::assertions::function::AssertSelfRef::mutable(); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; &self $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["&self"] $crate::assertions::function::AssertSelfRef::shared(); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; returns $ty:ty $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("returns ", stringify!($ty))] $crate::assertions::function::AssertReturns::from_type(
+            $crate::tools::syn::parse_quote!($ty)
+        ); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; name == $name:literal $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("name == ", stringify!($name))] $crate::assertions::function::AssertName::new($name); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; name ~ $pattern:literal $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("name ~ ", stringify!($pattern))] $crate::assertions::function::AssertNameMatches::new($pattern); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; extern $abi:literal $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; [concat!("extern ", stringify!($abi))] $crate::assertions::function::AssertExternAbi::named($abi); $($($rest)*)?)
+    };
+
+    (@clauses $all:ident; extern $(, $($rest:tt)*)?) => {
+        $crate::assert_fn!(@clause $all; ["extern"] $crate::assertions::function::AssertExternAbi::any(); $($($rest)*)?)
+    };
+
+    (@clause $all:ident; [$clause:expr] $assertion:expr; $($rest:tt)*) => {{
+        let $all = $all.and($crate::assertions::traits::AstAssertion::<$crate::ast::Function>::message(
+            $assertion,
+            format!("requirement `{}` is not met", $clause),
+        ));
+        $crate::assert_fn!(@clauses $all; $($rest)*)
+    }};
+
+    (@clauses $all:ident; $($unknown:tt)*) => {
+        compile_error!(concat!("unknown assert_fn! clause at `", stringify!($($unknown)*), "`"))
+    };
+
+    ($($clauses:tt)*) => {{
+        let all = $crate::assertions::combinators::All::<$crate::ast::Function>::new();
+        $crate::assert_fn!(@clauses all; $($clauses)*)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::Function, error::Errors, impl_prelude::*};
+
+    fn failures(assertion: impl AstAssertion<Function>, input: Tokens) -> Vec<String> {
+        let function: Function = syn::parse2::<syn::ItemFn>(input).unwrap().into();
+
+        let mut errors = Errors::new();
+        errors.handle(assertion.assert(&function));
+
+        errors.iter().map(|error| error.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_each_failed_clause() {
+        let assertion = assert_fn!(async, pub, params == 2, name ~ "test_*");
+        let input = tokens!(
+            pub fn check(a: u8) {}
+        );
+
+        assert_eq!(
+            failures(assertion, input),
+            [
+                "requirement `async` is not met",
+                "requirement `params == 2` is not met",
+                "requirement `name ~ \"test_*\"` is not met",
+            ]
+        );
+    }
+
+    #[test]
+    fn names_clauses_without_token_spacing() {
+        let assertion = assert_fn!(!async, &self, returns Result<_, _>);
+        let input = tokens!(
+            async fn check(self) -> u8 {}
+        );
+
+        assert_eq!(
+            failures(assertion, input),
+            [
+                "requirement `!async` is not met",
+                "requirement `&self` is not met",
+                "requirement `returns Result<_, _>` is not met",
+            ]
+        );
+    }
+}
@@ -1,8 +1,12 @@
 use crate::{
-    assertions::{traits::AssertResult, utils::assert_some},
-    ast::{Function, Identifier, Signature},
-    error::assertions::Property,
+    assertions::{
+        traits::AssertResult,
+        utils::{glob_matches, type_matches},
+    },
+    ast::{FnParameter, Function, Identifier, Signature},
+    error::{assertions::Property, suggest},
     impl_prelude::*,
+    parse_tokens,
 };
 
 #[derive(Debug)]
@@ -12,11 +16,34 @@ impl AstAssertion<Function> for AssertAsync {
     fn assert(&self, node: &Function) -> AssertResult {
         self.assert(&node.signature())
     }
+
+    fn expectation(&self) -> String {
+        "async".into()
+    }
 }
 
 impl AstAssertion<Signature> for AssertAsync {
+    fn expectation(&self) -> String {
+        "async".into()
+    }
+
     fn assert(&self, node: &Signature) -> AssertResult {
-        assert_some(&node.asyncness(), "async", node)
+        if node.asyncness().is_some() {
+            return Ok(());
+        }
+
+        // `async` goes after `const` but before `unsafe` and `extern`.
+        let error = MacroError::expected("async").actual(node);
+        let error = match (node.unsafety(), node.extern_abi()) {
+            (Some(unsafety), _) => error.suggest(unsafety, tokens!(async unsafe), "add `async`"),
+            (None, Some(abi)) => {
+                let replacement = tokens!(async #abi);
+                error.suggest(abi, replacement, "add `async`")
+            }
+            (None, None) => error.suggest(node.fn_token(), tokens!(async fn), "add `async`"),
+        };
+
+        Err(error.into())
     }
 }
 
@@ -24,7 +51,6 @@ impl AstAssertion<Signature> for AssertAsync {
 pub struct AssertIdent {
     property: Property,
     value: String,
-    message: Option<String>,
 }
 
 impl AssertIdent {
@@ -32,22 +58,83 @@ impl AssertIdent {
         AssertIdent {
             property: property.into(),
             value: value.into(),
-            message: None,
         }
     }
 }
 
 impl AstAssertion<Identifier> for AssertIdent {
+    fn expectation(&self) -> String {
+        format!("{} `{}`", self.property, self.value)
+    }
+
     fn assert(&self, node: &Identifier) -> AssertResult {
         let node_name = node.to_string();
 
         if self.value == node_name {
             Ok(())
         } else {
-            Err(MacroError::expected_property(self.property.clone())
+            let mut error = MacroError::expected_property(self.property.clone())
                 .to_be(self.value.clone())
-                .but_was(node)
-                .into())
+                .but_was(node);
+
+            if suggest::closest(&node_name, Some(&self.value)).is_some() {
+                error = suggest::did_you_mean(error, node, Some(&self.value));
+            } else if let Ok(ident) = syn::parse_str::<syn::Ident>(&self.value) {
+                let node = Identifier::from(node.as_syn().clone());
+                error = error.suggest(node, ident, format!("rename it to `{}`", self.value));
+            }
+
+            Err(error.into())
+        }
+    }
+}
+
+/// Requires an identifier to be one of a set of allowed values, suggesting
+/// the closest one when it's a likely typo.
+#[derive(Debug)]
+pub struct AssertIdentOneOf {
+    property: Property,
+    values: Vec<String>,
+}
+
+impl AssertIdentOneOf {
+    pub fn new(
+        property: impl Into<Property>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> AssertIdentOneOf {
+        AssertIdentOneOf {
+            property: property.into(),
+            values: values.into_iter().map(|value| value.into()).collect(),
+        }
+    }
+
+    fn values(&self) -> String {
+        let values: Vec<String> = self
+            .values
+            .iter()
+            .map(|value| format!("`{}`", value))
+            .collect();
+
+        values.join(", ")
+    }
+}
+
+impl AstAssertion<Identifier> for AssertIdentOneOf {
+    fn expectation(&self) -> String {
+        format!("{} one of {}", self.property, self.values())
+    }
+
+    fn assert(&self, node: &Identifier) -> AssertResult {
+        let node_name = node.to_string();
+
+        if self.values.contains(&node_name) {
+            Ok(())
+        } else {
+            let error = MacroError::expected_property(self.property.clone())
+                .to_be(format!("one of {}", self.values()))
+                .but_was(node);
+
+            Err(suggest::did_you_mean(error, node, &self.values).into())
         }
     }
 }
@@ -67,10 +154,572 @@ impl AstAssertion<Function> for AssertName {
     fn assert(&self, node: &Function) -> AssertResult {
         self.assert(&node.signature())
     }
+
+    fn expectation(&self) -> String {
+        format!("named `{}`", self.name)
+    }
 }
 
 impl AstAssertion<Signature> for AssertName {
+    fn expectation(&self) -> String {
+        format!("named `{}`", self.name)
+    }
+
     fn assert(&self, node: &Signature) -> AssertResult {
         AssertIdent::new("function name", &self.name).assert(&node.function_name())
     }
 }
+
+/// Requires the function name to match a glob pattern, in which `*` matches
+/// any run of characters and `?` matches a single character.
+#[derive(Debug)]
+pub struct AssertNameMatches {
+    pattern: String,
+}
+
+impl AssertNameMatches {
+    pub fn new(pattern: impl Into<String>) -> AssertNameMatches {
+        AssertNameMatches {
+            pattern: pattern.into(),
+        }
+    }
+}
+
+impl AstAssertion<Function> for AssertNameMatches {
+    fn assert(&self, node: &Function) -> AssertResult {
+        self.assert(&node.signature())
+    }
+
+    fn expectation(&self) -> String {
+        format!("named like `{}`", self.pattern)
+    }
+}
+
+impl AstAssertion<Signature> for AssertNameMatches {
+    fn expectation(&self) -> String {
+        format!("named like `{}`", self.pattern)
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        let name = node.function_name();
+
+        if glob_matches(&self.pattern, &name.to_string()) {
+            Ok(())
+        } else {
+            Err(MacroError::expected_property("function name")
+                .to_be(format!("like `{}`", self.pattern))
+                .but_was(&name)
+                .into())
+        }
+    }
+}
+
+/// Implements `AstAssertion<Function>` for an assertion on the function's
+/// signature.
+macro_rules! signature_assertion {
+    ($assertion:ty) => {
+        impl AstAssertion<Function> for $assertion {
+            fn assert(&self, node: &Function) -> AssertResult {
+                self.assert(&node.signature())
+            }
+
+            fn expectation(&self) -> String {
+                AstAssertion::<Signature>::expectation(self)
+            }
+        }
+    };
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AssertVisibility {
+    /// `pub`
+    Public,
+    /// `pub(crate)` or `crate`
+    Crate,
+    /// No visibility modifier
+    Private,
+}
+
+impl AssertVisibility {
+    fn matches(&self, visibility: &syn::Visibility) -> bool {
+        match (self, visibility) {
+            (AssertVisibility::Public, syn::Visibility::Public(_)) => true,
+            (AssertVisibility::Crate, syn::Visibility::Crate(_)) => true,
+            (AssertVisibility::Crate, syn::Visibility::Restricted(restricted)) => {
+                restricted.path.is_ident("crate")
+            }
+            (AssertVisibility::Private, syn::Visibility::Inherited) => true,
+            _ => false,
+        }
+    }
+}
+
+impl AstAssertion<Function> for AssertVisibility {
+    fn expectation(&self) -> String {
+        match self {
+            AssertVisibility::Public => "`pub`",
+            AssertVisibility::Crate => "`pub(crate)`",
+            AssertVisibility::Private => "private",
+        }
+        .into()
+    }
+
+    fn assert(&self, node: &Function) -> AssertResult {
+        let visibility = node.visibility();
+
+        if self.matches(visibility.as_syn()) {
+            return Ok(());
+        }
+
+        let error = MacroError::expected_property(node.description()).to_be(self.expectation());
+
+        let replacement = match self {
+            AssertVisibility::Public => tokens!(pub),
+            AssertVisibility::Crate => tokens!(pub(crate)),
+            AssertVisibility::Private => Tokens::new(),
+        };
+
+        let message = match self {
+            AssertVisibility::Public => "make it `pub`",
+            AssertVisibility::Crate => "make it `pub(crate)`",
+            AssertVisibility::Private => "remove the visibility",
+        };
+
+        match visibility.as_syn() {
+            syn::Visibility::Inherited => {
+                // Visibility goes before any qualifiers, like `pub async fn`.
+                let first = node.signature().tokens().into_iter().next();
+
+                Err(error
+                    .but_was(&node.name())
+                    .suggest_at(first.span(), tokens!(#replacement #first), message)
+                    .into())
+            }
+            _ => Err(error
+                .but_was(&visibility)
+                .suggest(visibility, replacement, message)
+                .into()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AssertNotConst;
+
+signature_assertion!(AssertNotConst);
+
+impl AstAssertion<Signature> for AssertNotConst {
+    fn expectation(&self) -> String {
+        "not const".into()
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        match node.constness() {
+            Some(constness) => Err(MacroError::expected_property("function")
+                .to_not_be("const")
+                .but_was(&constness)
+                .suggest(constness, Tokens::new(), "remove `const`")
+                .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AssertNotUnsafe;
+
+signature_assertion!(AssertNotUnsafe);
+
+impl AstAssertion<Signature> for AssertNotUnsafe {
+    fn expectation(&self) -> String {
+        "not unsafe".into()
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        match node.unsafety() {
+            Some(unsafety) => Err(MacroError::expected_property("function")
+                .to_not_be("unsafe")
+                .but_was(&unsafety)
+                .suggest(unsafety, Tokens::new(), "remove `unsafe`")
+                .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Counts the parameters other than `self`.
+#[derive(Debug)]
+pub struct AssertParamCount {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl AssertParamCount {
+    pub fn exactly(count: usize) -> AssertParamCount {
+        AssertParamCount {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    pub fn at_least(count: usize) -> AssertParamCount {
+        AssertParamCount {
+            min: count,
+            max: None,
+        }
+    }
+
+    pub fn at_most(count: usize) -> AssertParamCount {
+        AssertParamCount {
+            min: 0,
+            max: Some(count),
+        }
+    }
+}
+
+signature_assertion!(AssertParamCount);
+
+impl AstAssertion<Signature> for AssertParamCount {
+    fn expectation(&self) -> String {
+        fn parameters(count: usize) -> String {
+            match count {
+                1 => "1 parameter".into(),
+                count => format!("{} parameters", count),
+            }
+        }
+
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => format!("taking {}", parameters(min)),
+            (0, Some(max)) => format!("taking at most {}", parameters(max)),
+            (min, None) => format!("taking at least {}", parameters(min)),
+            (min, Some(max)) => format!("taking {} to {}", min, parameters(max)),
+        }
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        let params: Vec<FnParameter> = node
+            .parameters()
+            .params()
+            .into_pairs()
+            .map(|pair| pair.into_item())
+            .collect();
+
+        let count = params.len();
+        let error = MacroError::expected_property("function")
+            .to_be(AstAssertion::<Signature>::expectation(self));
+
+        if count < self.min {
+            Err(error
+                .but_was(&node.function_name())
+                .info(format!("found {}", count))
+                .into())
+        } else if let Some(extra) = self.max.and_then(|max| params.get(max)) {
+            Err(error.but_was(extra).info(format!("found {}", count)).into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AssertNoGenerics;
+
+signature_assertion!(AssertNoGenerics);
+
+impl AstAssertion<Signature> for AssertNoGenerics {
+    fn expectation(&self) -> String {
+        "not generic".into()
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        match node.generic_params() {
+            Some(generics) => Err(MacroError::expected_property("function")
+                .to_not_be("generic")
+                .but_was(&generics)
+                .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AssertNoSelf;
+
+signature_assertion!(AssertNoSelf);
+
+impl AstAssertion<Signature> for AssertNoSelf {
+    fn expectation(&self) -> String {
+        "a function without `self`".into()
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        match node.parameters().self_param() {
+            Some(receiver) => Err(MacroError::expected_property("function")
+                .to_not_be("a method")
+                .but_was(&receiver)
+                .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Requires a `&self` or `&mut self` receiver.
+#[derive(Debug)]
+pub struct AssertSelfRef {
+    mutable: Option<bool>,
+}
+
+impl AssertSelfRef {
+    /// `&self` or `&mut self`
+    pub fn any() -> AssertSelfRef {
+        AssertSelfRef { mutable: None }
+    }
+
+    /// `&self`
+    pub fn shared() -> AssertSelfRef {
+        AssertSelfRef {
+            mutable: Some(false),
+        }
+    }
+
+    /// `&mut self`
+    pub fn mutable() -> AssertSelfRef {
+        AssertSelfRef {
+            mutable: Some(true),
+        }
+    }
+}
+
+signature_assertion!(AssertSelfRef);
+
+impl AstAssertion<Signature> for AssertSelfRef {
+    fn expectation(&self) -> String {
+        match self.mutable {
+            None => "a method taking `&self` or `&mut self`",
+            Some(false) => "a method taking `&self`",
+            Some(true) => "a method taking `&mut self`",
+        }
+        .into()
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        let error = MacroError::expected_property("function")
+            .to_be(AstAssertion::<Signature>::expectation(self));
+
+        match node.parameters().self_param() {
+            None => Err(error.but_was(&node.function_name()).into()),
+            Some(receiver) => {
+                let (replacement, message) = match self.mutable {
+                    Some(true) => (tokens!(&mut self), "take `&mut self`"),
+                    _ => (tokens!(&self), "take `&self`"),
+                };
+
+                match (receiver.borrow(), self.mutable) {
+                    (Some(borrowed_mut), Some(mutable)) if mutable != borrowed_mut => Err(error
+                        .but_was(&receiver)
+                        .suggest(receiver, replacement, message)
+                        .into()),
+                    (Some(_), _) => Ok(()),
+                    (None, _) => Err(error
+                        .but_was(&receiver)
+                        .suggest(receiver, replacement, message)
+                        .into()),
+                }
+            }
+        }
+    }
+}
+
+/// Requires the return type to match a type pattern, in which `_` stands for
+/// any type: `AssertReturns::new(ty!(Result<_, _>))`. A function without a
+/// return type returns `()`.
+#[derive(Debug)]
+pub struct AssertReturns {
+    pattern: syn::Type,
+}
+
+impl AssertReturns {
+    #[allow(clippy::result_large_err)]
+    pub fn new(pattern: Tokens) -> MacroResult<AssertReturns> {
+        Ok(AssertReturns::from_type(parse_tokens!(pattern)?))
+    }
+
+    pub fn from_type(pattern: syn::Type) -> AssertReturns {
+        AssertReturns { pattern }
+    }
+}
+
+signature_assertion!(AssertReturns);
+
+impl AstAssertion<Signature> for AssertReturns {
+    fn expectation(&self) -> String {
+        format!("returning `{}`", self.pattern.to_token_stream())
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        let return_type = node.return_type();
+
+        if type_matches(&self.pattern, &return_type.ty()) {
+            return Ok(());
+        }
+
+        let error = MacroError::expected_property("return type")
+            .to_be(format!("`{}`", self.pattern.to_token_stream()));
+
+        if return_type.as_syn() == &syn::ReturnType::Default {
+            Err(error.but_was(&node.function_name()).into())
+        } else {
+            Err(error.but_was(&return_type).into())
+        }
+    }
+}
+
+/// Requires an `extern` ABI, optionally a specific one. A bare `extern` is
+/// the `"C"` ABI.
+#[derive(Debug)]
+pub struct AssertExternAbi {
+    name: Option<String>,
+}
+
+impl AssertExternAbi {
+    pub fn any() -> AssertExternAbi {
+        AssertExternAbi { name: None }
+    }
+
+    pub fn named(name: impl Into<String>) -> AssertExternAbi {
+        AssertExternAbi {
+            name: Some(name.into()),
+        }
+    }
+}
+
+signature_assertion!(AssertExternAbi);
+
+impl AstAssertion<Signature> for AssertExternAbi {
+    fn expectation(&self) -> String {
+        match &self.name {
+            Some(name) => format!("extern {:?}", name),
+            None => "extern".into(),
+        }
+    }
+
+    fn assert(&self, node: &Signature) -> AssertResult {
+        let error = MacroError::expected_property("function")
+            .to_be(AstAssertion::<Signature>::expectation(self));
+
+        match (node.extern_abi(), &self.name) {
+            (None, _) => Err(error.but_was(&node.function_name()).into()),
+            (Some(_), None) => Ok(()),
+            (Some(abi), Some(name)) => {
+                let actual = abi
+                    .as_syn()
+                    .name
+                    .as_ref()
+                    .map(|lit| lit.value())
+                    .unwrap_or_else(|| "C".into());
+
+                if &actual == name {
+                    Ok(())
+                } else {
+                    Err(error.but_was(&abi).into())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn async_suggestion(input: Tokens) -> String {
+        let function: Function = syn::parse2::<syn::ItemFn>(input).unwrap().into();
+        let error = AssertAsync.assert(&function).unwrap_err();
+
+        error.suggestions()[0].replacement().to_string()
+    }
+
+    #[test]
+    fn suggests_async_before_fn() {
+        assert_eq!(
+            async_suggestion(tokens!(
+                fn f() {}
+            )),
+            "async fn"
+        );
+        assert_eq!(
+            async_suggestion(tokens!(
+                const fn f() {}
+            )),
+            "async fn"
+        );
+    }
+
+    #[test]
+    fn suggests_async_before_unsafe_and_extern() {
+        assert_eq!(
+            async_suggestion(tokens!(
+                const unsafe fn f() {}
+            )),
+            "async unsafe"
+        );
+        assert_eq!(
+            async_suggestion(tokens!(
+                unsafe extern "C" fn f() {}
+            )),
+            "async unsafe"
+        );
+        assert_eq!(
+            async_suggestion(tokens!(
+                extern "C" fn f() {}
+            )),
+            "async extern \"C\""
+        );
+    }
+
+    fn signature(input: Tokens) -> Signature {
+        syn::parse2::<syn::ItemFn>(input).unwrap().sig.into()
+    }
+
+    #[test]
+    fn treats_typed_self_as_the_receiver() {
+        let boxed = signature(tokens!(
+            fn f(self: Box<Self>, x: u8) {}
+        ));
+        let pinned = signature(tokens!(
+            fn f(self: Pin<&mut Self>) {}
+        ));
+
+        assert!(AssertParamCount::exactly(1).assert(&boxed).is_ok());
+        assert!(AssertNoSelf.assert(&boxed).is_err());
+        assert!(AssertNoSelf.assert(&pinned).is_err());
+        assert!(AssertSelfRef::any().assert(&boxed).is_err());
+    }
+
+    #[test]
+    fn accepts_typed_self_references() {
+        let shared = signature(tokens!(
+            fn f(self: &Self) {}
+        ));
+        let mutable = signature(tokens!(
+            fn f(self: &mut Self) {}
+        ));
+
+        assert!(AssertSelfRef::shared().assert(&shared).is_ok());
+        assert!(AssertSelfRef::mutable().assert(&shared).is_err());
+        assert!(AssertSelfRef::mutable().assert(&mutable).is_ok());
+    }
+
+    #[test]
+    fn accepts_every_spelling_of_crate_visibility() {
+        // As strings, since rustfmt rewrites `pub(in crate)` to `pub(crate)`.
+        let is_crate = |input: &str| {
+            let function: Function = syn::parse_str::<syn::ItemFn>(input).unwrap().into();
+            AssertVisibility::Crate.assert(&function).is_ok()
+        };
+
+        assert!(is_crate("pub(crate) fn f() {}"));
+        assert!(is_crate("pub(in crate) fn f() {}"));
+        assert!(!is_crate("pub(super) fn f() {}"));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod combinators;
+#[macro_use]
+pub mod dsl;
 pub mod function;
 pub mod traits;
 pub mod utils;
@@ -1,14 +1,41 @@
 use std::fmt::Debug;
 
-use crate::{error::assertions::DiagnosticError, impl_prelude::*};
+use crate::{error::Errors, impl_prelude::*};
 
-pub type AssertResult = Result<(), DiagnosticError>;
+pub type AssertResult = MacroResult<()>;
+
+/// Runs every assertion against `node`, reporting all of the failures rather
+/// than stopping at the first one.
+#[allow(clippy::result_large_err)]
+pub fn assert_all<N>(assertions: &[Assertion<N>], node: &N) -> MacroResult<()>
+where
+    N: AstNode + Debug,
+{
+    let mut errors = Errors::new();
+
+    errors.extend(
+        assertions
+            .iter()
+            .filter_map(|assertion| assertion.assert(node).err()),
+    );
+
+    errors.finish()
+}
 
 pub trait AstAssertion<Node>: Debug + 'static
 where
     Node: AstNode,
 {
+    #[allow(clippy::result_large_err)]
     fn assert(&self, node: &Node) -> AssertResult;
+
+    /// A short phrase describing what the assertion expects, which reads
+    /// naturally after "expected function to be", e.g. "async". Combinators
+    /// use it to describe their operands.
+    fn expectation(&self) -> String {
+        format!("{:?}", self)
+    }
+
     fn assertion(self) -> Assertion<Node>
     where
         Self: Sized,
@@ -44,6 +71,10 @@ where
         self
     }
 
+    fn expectation(&self) -> String {
+        self.assertion.expectation()
+    }
+
     fn assert(&self, node: &N) -> AssertResult {
         self.assertion
             .assert(node)
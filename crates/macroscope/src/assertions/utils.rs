@@ -1,12 +1,163 @@
-use crate::{assertions::traits::AssertResult, impl_prelude::*};
-
-pub(crate) fn assert_some<T>(
-    option: &Option<T>,
-    expected: impl Into<String>,
-    actual: &impl AstNode,
-) -> AssertResult {
-    match option.as_ref() {
-        Some(_) => Ok(()),
-        None => Err(MacroError::expected(expected).actual(actual)),
+use crate::impl_prelude::*;
+
+/// Whether `name` matches a glob `pattern`, in which `*` matches any run of
+/// characters and `?` matches a single character.
+pub(crate) fn glob_matches(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => (0..=name.len()).any(|skip| matches(rest, &name[skip..])),
+            Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && matches(rest, &name[1..]),
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    matches(&pattern, &name)
+}
+
+/// Whether `actual` has the shape of `pattern`, where `_` in the pattern
+/// matches any type. Paths are compared from the end, so `Result<_, _>`
+/// matches `std::io::Result<u8, Error>` too.
+pub(crate) fn type_matches(pattern: &syn::Type, actual: &syn::Type) -> bool {
+    use syn::Type;
+
+    match (pattern, actual) {
+        (Type::Infer(_), _) => true,
+        (Type::Paren(pattern), _) => type_matches(&pattern.elem, actual),
+        (_, Type::Paren(actual)) => type_matches(pattern, &actual.elem),
+        (Type::Group(pattern), _) => type_matches(&pattern.elem, actual),
+        (_, Type::Group(actual)) => type_matches(pattern, &actual.elem),
+        (Type::Path(pattern), Type::Path(actual)) => {
+            pattern.qself.is_none() && path_matches(&pattern.path, &actual.path)
+        }
+        (Type::Reference(pattern), Type::Reference(actual)) => {
+            pattern.mutability.is_some() == actual.mutability.is_some()
+                && type_matches(&pattern.elem, &actual.elem)
+        }
+        (Type::Tuple(pattern), Type::Tuple(actual)) => {
+            pattern.elems.len() == actual.elems.len()
+                && pattern
+                    .elems
+                    .iter()
+                    .zip(actual.elems.iter())
+                    .all(|(pattern, actual)| type_matches(pattern, actual))
+        }
+        (Type::Slice(pattern), Type::Slice(actual)) => type_matches(&pattern.elem, &actual.elem),
+        (Type::Array(pattern), Type::Array(actual)) => {
+            type_matches(&pattern.elem, &actual.elem) && tokens_match(&pattern.len, &actual.len)
+        }
+        (Type::Ptr(pattern), Type::Ptr(actual)) => {
+            pattern.mutability.is_some() == actual.mutability.is_some()
+                && type_matches(&pattern.elem, &actual.elem)
+        }
+        (pattern, actual) => tokens_match(pattern, actual),
+    }
+}
+
+fn path_matches(pattern: &syn::Path, actual: &syn::Path) -> bool {
+    if pattern.segments.len() > actual.segments.len() {
+        return false;
+    }
+
+    pattern
+        .segments
+        .iter()
+        .rev()
+        .zip(actual.segments.iter().rev())
+        .all(|(pattern, actual)| {
+            pattern.ident == actual.ident && arguments_match(&pattern.arguments, &actual.arguments)
+        })
+}
+
+fn arguments_match(pattern: &syn::PathArguments, actual: &syn::PathArguments) -> bool {
+    use syn::{GenericArgument, PathArguments};
+
+    match (pattern, actual) {
+        (PathArguments::None, _) => true,
+        (PathArguments::AngleBracketed(pattern), PathArguments::AngleBracketed(actual)) => {
+            pattern.args.len() == actual.args.len()
+                && pattern
+                    .args
+                    .iter()
+                    .zip(actual.args.iter())
+                    .all(|(pattern, actual)| match (pattern, actual) {
+                        (GenericArgument::Type(pattern), GenericArgument::Type(actual)) => {
+                            type_matches(pattern, actual)
+                        }
+                        (pattern, actual) => tokens_match(pattern, actual),
+                    })
+        }
+        (pattern, actual) => tokens_match(pattern, actual),
+    }
+}
+
+fn tokens_match(pattern: &impl ToTokens, actual: &impl ToTokens) -> bool {
+    pattern.to_token_stream().to_string() == actual.to_token_stream().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, actual: &str) -> bool {
+        let pattern: syn::Type = syn::parse_str(pattern).unwrap();
+        let actual: syn::Type = syn::parse_str(actual).unwrap();
+
+        type_matches(&pattern, &actual)
+    }
+
+    #[test]
+    fn globs_match_runs_and_single_characters() {
+        assert!(glob_matches("get_*", "get_name"));
+        assert!(glob_matches("get_*", "get_"));
+        assert!(glob_matches("*_mut", "as_mut"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("a*b*c", "axxbyyc"));
+        assert!(glob_matches("?et", "set"));
+        assert!(glob_matches("new", "new"));
+
+        assert!(!glob_matches("get_*", "set_name"));
+        assert!(!glob_matches("?et", "et"));
+        assert!(!glob_matches("new", "new_with"));
+        assert!(!glob_matches("a*b*c", "axxbyy"));
+    }
+
+    #[test]
+    fn underscores_match_any_type() {
+        assert!(matches("_", "Vec<u8>"));
+        assert!(matches("Vec<_>", "Vec<String>"));
+        assert!(matches("Result<_, _>", "Result<u8, io::Error>"));
+        assert!(matches("(_, u8)", "(String, u8)"));
+        assert!(matches("[_; 4]", "[u8; 4]"));
+
+        assert!(!matches("Vec<_>", "Option<u8>"));
+        assert!(!matches("Result<_, _>", "Result<u8>"));
+        assert!(!matches("(_, u8)", "(String, u16)"));
+        assert!(!matches("[_; 4]", "[u8; 8]"));
+    }
+
+    #[test]
+    fn paths_are_compared_from_the_end() {
+        assert!(matches("Result<_, _>", "std::io::Result<u8, Error>"));
+        assert!(matches("io::Result<_>", "std::io::Result<u8>"));
+        assert!(matches("Vec", "Vec<u8>"));
+
+        assert!(!matches("std::io::Result<_>", "io::Result<u8>"));
+        assert!(!matches("fmt::Result", "io::Result"));
+    }
+
+    #[test]
+    fn references_and_pointers_keep_their_mutability() {
+        assert!(matches("&_", "&str"));
+        assert!(matches("&mut _", "&mut Vec<u8>"));
+        assert!(matches("&(_)", "&str"));
+        assert!(matches("*const _", "*const u8"));
+
+        assert!(!matches("&_", "&mut str"));
+        assert!(!matches("&mut _", "&str"));
+        assert!(!matches("*mut _", "*const u8"));
     }
 }
@@ -5,6 +5,25 @@ ast_newtype!(Function {
     inner: syn::ItemFn
 });
 
+impl From<syn::ImplItemMethod> for Function {
+    fn from(method: syn::ImplItemMethod) -> Function {
+        let syn::ImplItemMethod {
+            attrs,
+            vis,
+            sig,
+            block,
+            ..
+        } = method;
+
+        Function::from(syn::ItemFn {
+            attrs,
+            vis,
+            sig,
+            block: Box::new(block),
+        })
+    }
+}
+
 impl Function {
     pub fn name(&self) -> Identifier {
         self.inner.sig.ident.clone().into()
@@ -0,0 +1,6 @@
+use crate::impl_prelude::*;
+
+ast_newtype!(Impl {
+    description: "impl block",
+    inner: syn::ItemImpl
+});
@@ -59,7 +59,7 @@ where
     T: syn::token::Token + ToTokens,
 {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        tokens.extend(tokens! { #self })
+        self.inner.to_tokens(tokens)
     }
 }
 
@@ -6,12 +6,14 @@
 pub(crate) mod newtype;
 
 mod function;
+mod impl_block;
 mod leaf;
 pub mod punctuated;
 mod signature;
 mod traits;
 
-pub use self::function::Function;
+pub use self::function::{Attribute, Block, Function, Visibility};
+pub use self::impl_block::Impl;
 pub use self::leaf::*;
 pub use self::signature::*;
 pub use self::traits::{AstNode, AstPart};
@@ -85,6 +85,10 @@ macro_rules! token_newtype {
             fn validate(stream: &$crate::tools::syn::parse::ParseStream) -> bool {
                 <<Self as AstNode>::Inner as syn::token::Token>::peek(stream.cursor())
             }
+
+            fn expected() -> Vec<String> {
+                vec![<<Self as AstNode>::Inner as syn::token::Token>::display().to_string()]
+            }
         }
 
         impl Default for $ty {
@@ -21,6 +21,33 @@ impl Signature {
         Some(GenericParams { inner: angled })
     }
 
+    /// The `::<...>` arguments needed to call this function with its own type
+    /// and const parameters. Lifetimes are left to inference, since late-bound
+    /// lifetimes can't be specified explicitly.
+    pub fn turbofish(&self) -> Tokens {
+        let args: Vec<&syn::Ident> = self
+            .inner
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                syn::GenericParam::Type(ty) => Some(&ty.ident),
+                syn::GenericParam::Const(constant) => Some(&constant.ident),
+                syn::GenericParam::Lifetime(_) => None,
+            })
+            .collect();
+
+        if args.is_empty() {
+            Tokens::new()
+        } else {
+            tokens!(::<#(#args),*>)
+        }
+    }
+
+    pub fn fn_token(&self) -> LeafToken<SynToken![fn]> {
+        self.inner.fn_token.into()
+    }
+
     pub fn function_name(&self) -> Identifier {
         self.inner.ident.clone().into()
     }
@@ -114,7 +141,8 @@ ast_part!(Unsafe in Signature {
     remove(signature) signature.unsafety = None;
 });
 
-#[derive(SynParse)]
+#[derive(Syntax)]
+#[syntax(no_validate)]
 pub struct Qualifiers {
     constness: Optional<Const>,
     asyncness: Optional<Async>,
@@ -122,19 +150,6 @@ pub struct Qualifiers {
     abi: Option<syn::Abi>,
 }
 
-impl ToTokens for Qualifiers {
-    fn to_tokens(&self, tokens: &mut Tokens) {
-        let Self {
-            asyncness,
-            constness,
-            unsafety,
-            abi,
-        } = self;
-
-        tokens.extend(tokens!(#asyncness #constness #unsafety #abi));
-    }
-}
-
 ast_newtype!(WhereClause {
     description: "where clause",
     inner: syn::WhereClause
@@ -148,9 +163,12 @@ ast_newtype!(FnParameters {
 impl FnParameters {
     fn self_pair(&self) -> Option<Pair<SelfParameter, SynToken![,]>> {
         self.inner.clone().into_pairs().find_map(|pair| {
-            pair.flat_map(|item| match item {
-                syn::FnArg::Receiver(receiver) => Some(SelfParameter::from(receiver.clone())),
-                syn::FnArg::Typed(_) => None,
+            pair.flat_map(|item| {
+                if is_self(item) {
+                    Some(SelfParameter::from(item.clone()))
+                } else {
+                    None
+                }
             })
         })
     }
@@ -163,11 +181,15 @@ impl FnParameters {
         self.self_pair()?.into_separator()
     }
 
+    pub fn has_self(&self) -> bool {
+        self.self_pair().is_some()
+    }
+
     pub fn params(&self) -> Separated<FnParameter, SynToken![,]> {
         let iter = self.inner.clone().into_pairs().filter_map(|param| {
             param.flat_map(|p| match p {
-                syn::FnArg::Receiver(_) => None,
-                syn::FnArg::Typed(param) => Some(FnParameter::from(param.clone())),
+                syn::FnArg::Typed(param) if !is_self(p) => Some(FnParameter::from(param.clone())),
+                _ => None,
             })
         });
 
@@ -175,23 +197,112 @@ impl FnParameters {
     }
 }
 
+ast_part!(FnParameters in Signature {
+    add(signature, parameters) signature.inputs = parameters.inner.clone().into_punctuated();
+    remove(signature) signature.inputs = Default::default();
+});
+
+/// Whether `arg` is the receiver. syn parses a receiver with an explicit type,
+/// like `self: Box<Self>`, as a typed parameter named `self`.
+fn is_self(arg: &syn::FnArg) -> bool {
+    match arg {
+        syn::FnArg::Receiver(_) => true,
+        syn::FnArg::Typed(typed) => {
+            matches!(&*typed.pat, syn::Pat::Ident(binding) if binding.ident == "self")
+        }
+    }
+}
+
 ast_newtype!(SelfParameter {
     description: "self parameter",
-    inner: syn::Receiver
+    inner: syn::FnArg
 });
 
+impl SelfParameter {
+    /// Whether `self` is borrowed mutably, or `None` if it's taken by value.
+    /// Both `&mut self` and `self: &mut Self` are mutable borrows.
+    pub fn borrow(&self) -> Option<bool> {
+        match &self.inner {
+            syn::FnArg::Receiver(receiver) => receiver
+                .reference
+                .as_ref()
+                .map(|_| receiver.mutability.is_some()),
+            syn::FnArg::Typed(typed) => match &*typed.ty {
+                syn::Type::Reference(reference) => Some(reference.mutability.is_some()),
+                _ => None,
+            },
+        }
+    }
+}
+
 ast_newtype!(FnParameter { description: "function parameter", inner: syn::PatType } no Parse);
 
+impl FnParameter {
+    pub fn pattern(&self) -> syn::Pat {
+        (*self.inner.pat).clone()
+    }
+
+    pub fn ty(&self) -> syn::Type {
+        (*self.inner.ty).clone()
+    }
+
+    /// The identifier bound by this parameter, if its pattern is a plain
+    /// binding like `x` or `mut x`. Destructuring patterns, `_` and `ref`
+    /// bindings don't have one.
+    pub fn binding(&self) -> Option<Identifier> {
+        match &*self.inner.pat {
+            syn::Pat::Ident(syn::PatIdent {
+                by_ref: None,
+                subpat: None,
+                ident,
+                ..
+            }) => Some(ident.clone().into()),
+            _ => None,
+        }
+    }
+}
+
 ast_newtype!(ReturnType {
     description: "return type",
     inner: syn::ReturnType
 });
 
+impl From<syn::Type> for ReturnType {
+    fn from(ty: syn::Type) -> ReturnType {
+        ReturnType::from(syn::ReturnType::Type(Default::default(), Box::new(ty)))
+    }
+}
+
+impl ReturnType {
+    /// The returned type, with a missing return type treated as `()`.
+    pub fn ty(&self) -> syn::Type {
+        match &self.inner {
+            syn::ReturnType::Default => syn::parse_quote!(()),
+            syn::ReturnType::Type(_, ty) => (**ty).clone(),
+        }
+    }
+
+    pub fn is_unit(&self) -> bool {
+        matches!(self.ty(), syn::Type::Tuple(tuple) if tuple.elems.is_empty())
+    }
+
+    /// Transforms the returned type, e.g. from `T` to `Result<T, E>`. A
+    /// missing return type is passed to `mapper` as `()`.
+    pub fn map(&self, mapper: impl FnOnce(syn::Type) -> syn::Type) -> ReturnType {
+        ReturnType::from(mapper(self.ty()))
+    }
+}
+
+ast_part!(ReturnType in Signature {
+    add(signature, output) signature.output = output.inner.clone();
+    remove(signature) signature.output = syn::ReturnType::Default;
+});
+
 ast_newtype!(ExternAbi {
     description: |abi| {
         match &abi.name {
             Some(lit) => format!("extern {:?}", lit.value()),
-            None => format!("extern"),
+            None => "extern".to_string(),
         }
     },
     inner: syn::Abi
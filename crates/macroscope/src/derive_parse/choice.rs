@@ -1,8 +1,118 @@
 use crate::tools::proc_macro2;
 use crate::tools::quote::{quote, ToTokens};
-use crate::tools::syn::{self, parse::Parse};
+use crate::tools::syn::{
+    self,
+    buffer::Cursor,
+    parse::{discouraged::Speculative, Parse, ParseStream},
+};
 
-use crate::derive_parse::validate::Validate;
+use crate::derive_parse::{
+    lookahead::{self, Scope},
+    validate::Validate,
+};
+
+/// Tries alternatives in order on forks of the input, remembering why each
+/// one failed so that the final error can list all of them.
+pub struct Alternatives<'a> {
+    input: ParseStream<'a>,
+    failures: Vec<Failure<'a>>,
+    _scope: Scope,
+}
+
+/// Where an alternative stopped, its error and what that error expected.
+struct Failure<'a> {
+    cursor: Cursor<'a>,
+    error: syn::Error,
+    expected: Option<Vec<String>>,
+}
+
+impl<'a> Alternatives<'a> {
+    pub fn new(input: ParseStream<'a>) -> Alternatives<'a> {
+        Alternatives {
+            input,
+            failures: vec![],
+            _scope: Scope::enter(),
+        }
+    }
+
+    /// Runs `parse` on a fork, advancing the input if it succeeds.
+    pub fn attempt<T>(&mut self, parse: impl FnOnce(ParseStream) -> syn::Result<T>) -> Option<T> {
+        let fork = self.input.fork();
+
+        match lookahead::speculate(|| parse(&fork)) {
+            Ok(parsed) => {
+                self.input.advance_to(&fork);
+                Some(parsed)
+            }
+            Err(error) => {
+                self.failures.push(Failure {
+                    cursor: fork.cursor(),
+                    expected: lookahead::expectations(&error),
+                    error,
+                });
+                None
+            }
+        }
+    }
+
+    /// The error for when every alternative failed. If some alternatives got
+    /// further than others before failing, their errors are the relevant
+    /// ones; otherwise the error lists what each alternative expected, next
+    /// to any errors that don't say.
+    pub fn into_error(self) -> syn::Error {
+        let start = self.input.cursor();
+
+        let mut furthest = start;
+        for failure in &self.failures {
+            if failure.cursor > furthest {
+                furthest = failure.cursor;
+            }
+        }
+
+        if furthest != start {
+            let mut errors = self
+                .failures
+                .into_iter()
+                .filter(|failure| failure.cursor == furthest)
+                .map(|failure| failure.error);
+
+            // These errors are past the start of the input, so what they
+            // expected couldn't have appeared here.
+            let first = errors.next().expect("the furthest cursor is a failure's");
+            return lookahead::unexplained(combine(first, errors));
+        }
+
+        let mut expected = vec![];
+        let mut others = vec![];
+
+        for failure in self.failures {
+            match failure.expected {
+                Some(items) => expected.extend(items),
+                None => others.push(failure.error),
+            }
+        }
+
+        if others.is_empty() {
+            return lookahead::expected_one_of(self.input.span(), expected);
+        }
+
+        let mut others = others.into_iter();
+        let first = if expected.is_empty() {
+            others.next().expect("others isn't empty")
+        } else {
+            lookahead::expected_one_of(self.input.span(), expected)
+        };
+
+        lookahead::unexplained(combine(first, others))
+    }
+}
+
+fn combine(first: syn::Error, rest: impl IntoIterator<Item = syn::Error>) -> syn::Error {
+    rest.into_iter().fold(first, |mut combined, error| {
+        combined.combine(error);
+        combined
+    })
+}
 
 #[derive(Debug)]
 pub enum OrderedChoice<T, U>
@@ -22,6 +132,12 @@ where
     fn validate(stream: &syn::parse::ParseStream) -> bool {
         T::validate(stream) || U::validate(stream)
     }
+
+    fn expected() -> Vec<String> {
+        let mut expected = T::expected();
+        expected.extend(U::expected());
+        expected
+    }
 }
 
 impl<T, U> ToTokens for OrderedChoice<T, U>
@@ -43,8 +159,169 @@ where
     U: Parse,
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        try_parse!(input => T)
-            .map(|left| Ok(OrderedChoice::Left(left)))
-            .unwrap_or_else(|| U::parse(input).map(|right| OrderedChoice::Right(right)))
+        let mut alternatives = Alternatives::new(input);
+
+        if let Some(left) = alternatives.attempt(T::parse) {
+            return Ok(OrderedChoice::Left(left));
+        }
+
+        if let Some(right) = alternatives.attempt(U::parse) {
+            return Ok(OrderedChoice::Right(right));
+        }
+
+        Err(alternatives.into_error())
+    }
+}
+
+macro_rules! choice {
+    ($name:ident { $($variant:ident($ty:ident)),* }) => {
+        #[derive(Debug)]
+        pub enum $name<$($ty),*>
+        where
+            $($ty: Parse),*
+        {
+            $($variant($ty)),*
+        }
+
+        impl<$($ty),*> Validate for $name<$($ty),*>
+        where
+            $($ty: Parse + Validate),*
+        {
+            fn validate(stream: &syn::parse::ParseStream) -> bool {
+                false $(|| $ty::validate(stream))*
+            }
+
+            fn expected() -> Vec<String> {
+                let mut expected = vec![];
+                $(expected.extend($ty::expected());)*
+                expected
+            }
+        }
+
+        impl<$($ty),*> ToTokens for $name<$($ty),*>
+        where
+            $($ty: Parse + ToTokens),*
+        {
+            fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+                match self {
+                    $($name::$variant(item) => item.to_tokens(tokens)),*
+                }
+            }
+        }
+
+        impl<$($ty),*> Parse for $name<$($ty),*>
+        where
+            $($ty: Parse),*
+        {
+            fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+                let mut alternatives = Alternatives::new(input);
+
+                $(
+                    if let Some(item) = alternatives.attempt($ty::parse) {
+                        return Ok($name::$variant(item));
+                    }
+                )*
+
+                Err(alternatives.into_error())
+            }
+        }
+    };
+}
+
+// `OrderedChoice` with more alternatives, tried in order.
+choice!(Choice3 { First(A), Second(B), Third(C) });
+choice!(Choice4 { First(A), Second(B), Third(C), Fourth(D) });
+choice!(Choice5 { First(A), Second(B), Third(C), Fourth(D), Fifth(E) });
+choice!(Choice6 { First(A), Second(B), Third(C), Fourth(D), Fifth(E), Sixth(F) });
+choice!(Choice7 { First(A), Second(B), Third(C), Fourth(D), Fifth(E), Sixth(F), Seventh(G) });
+choice!(Choice8 {
+    First(A), Second(B), Third(C), Fourth(D), Fifth(E), Sixth(F), Seventh(G), Eighth(H)
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_parse::{delimited::Parenthesized, pair::Pair};
+    use crate::tools::syn::{Ident, LitInt, Token};
+    use crate::Syntax;
+
+    fn error<T: Parse>(input: crate::Tokens) -> String {
+        match syn::parse2::<T>(input) {
+            Ok(_) => panic!("expected a parse error"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    type Item = Choice3<Token![fn], Token![struct], Token![enum]>;
+
+    type Keyword = Choice8<
+        Token![fn],
+        Token![struct],
+        Token![enum],
+        Token![trait],
+        Token![impl],
+        Token![type],
+        Token![const],
+        Token![static],
+    >;
+
+    type Binding =
+        Choice3<Token![fn], Pair<Pair<Token![let], Ident>, Pair<Token![=], LitInt>>, Ident>;
+
+    #[test]
+    fn picks_the_first_alternative_that_parses() {
+        let parsed: Choice3<Token![fn], Ident, Token![enum]> = syn::parse2(quote!(name)).unwrap();
+        assert!(matches!(parsed, Choice3::Second(_)));
+
+        let parsed: Keyword = syn::parse2(quote!(static)).unwrap();
+        assert!(matches!(parsed, Choice8::Eighth(_)));
+    }
+
+    #[test]
+    fn lists_every_alternative_when_all_of_them_fail() {
+        assert_eq!(
+            error::<Item>(quote!(mod)),
+            "expected one of `fn`, `struct`, `enum`"
+        );
+        assert_eq!(
+            error::<Keyword>(quote!(mod)),
+            "expected one of `fn`, `struct`, `enum`, `trait`, `impl`, `type`, `const`, `static`"
+        );
+    }
+
+    #[test]
+    fn reports_the_alternative_that_got_furthest() {
+        assert_eq!(
+            error::<Binding>(quote!(let a = b)),
+            "expected integer literal"
+        );
+    }
+
+    #[derive(Debug, Syntax)]
+    enum Statement {
+        Let {
+            let_token: Token![let],
+            name: Ident,
+            eq: Token![=],
+            value: LitInt,
+        },
+        Call(Ident, Parenthesized<LitInt>),
+        Return(Token![return], Ident),
+    }
+
+    #[test]
+    fn tries_enum_variants_in_order() {
+        let parsed: Statement = syn::parse2(quote!(f(1))).unwrap();
+        assert!(matches!(parsed, Statement::Call(..)));
+
+        assert_eq!(
+            error::<Statement>(quote!(1)),
+            "expected one of `let`, identifier, `return`"
+        );
+        assert_eq!(
+            error::<Statement>(quote!(let a = b)),
+            "expected integer literal"
+        );
+        assert_eq!(error::<Statement>(quote!(return 1)), "expected identifier");
     }
 }
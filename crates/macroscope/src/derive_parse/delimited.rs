@@ -11,7 +11,7 @@ use crate::tools::syn::{
     Token,
 };
 
-use crate::derive_parse::wrapper::HasParts;
+use crate::derive_parse::{lookahead, wrapper::HasParts};
 
 pub trait Delimiter: Sized + Debug + Copy + 'static {
     fn parse<T>(input: ParseStream) -> syn::Result<(Self, T)>
@@ -48,7 +48,7 @@ impl Delimiter for Paren {
         let content;
 
         let paren = parenthesized!(content in input);
-        let inner = T::parse(&content)?;
+        let inner = lookahead::isolate(|| T::parse(&content))?;
 
         Ok((paren, inner))
     }
@@ -66,7 +66,7 @@ impl Delimiter for Brace {
         let content;
 
         let paren = braced!(content in input);
-        let inner = T::parse(&content)?;
+        let inner = lookahead::isolate(|| T::parse(&content))?;
 
         Ok((paren, inner))
     }
@@ -84,7 +84,7 @@ impl Delimiter for Bracket {
         let content;
 
         let paren = bracketed!(content in input);
-        let inner = T::parse(&content)?;
+        let inner = lookahead::isolate(|| T::parse(&content))?;
 
         Ok((paren, inner))
     }
@@ -0,0 +1,470 @@
+//! Tracks what could have appeared at the current position, so that parse
+//! errors read "expected one of `const`, `async`, `unsafe`, `fn`" even when
+//! most of those were optional parts that parsed as missing.
+//!
+//! `Optional`, `ListWithTail`, `Repeat` and `SeparatedInline` record what they
+//! looked for when they match nothing, and [`Sequence`] merges those records
+//! into the error of the next part that fails without consuming anything.
+//! Records made by a failed [`speculate`] or inside a group are dropped with
+//! it, since they're about positions the parse never continues from.
+//!
+//! Errors built by [`expected_one_of`] remember the items they list, so a
+//! sequence or choice around them can merge them again.
+//!
+//! All of this only lives as long as the outermost parse that tracks it: the
+//! first [`Sequence`], [`Alternatives`](crate::derive_parse::choice::Alternatives)
+//! or [`speculate`] opens a [`Scope`], and everything is forgotten once that
+//! scope ends.
+
+use std::cell::RefCell;
+
+use crate::tools::proc_macro2::Span;
+use crate::tools::syn::{
+    self,
+    parse::{Parse, ParseStream},
+};
+
+struct State {
+    /// How many [`Scope`]s are open.
+    depth: usize,
+    /// What could have appeared where parsing stopped, oldest first.
+    missed: Vec<String>,
+    /// The error most recently built by [`expected_one_of`] or
+    /// [`unexplained`], if no other parse has started since.
+    explained: Option<Explained>,
+}
+
+/// What an error built here expected, or `None` if it isn't about what could
+/// appear where it points. The message tells it apart from an error that
+/// other code returned instead.
+struct Explained {
+    message: String,
+    expected: Option<Vec<String>>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = const {
+        RefCell::new(State {
+            depth: 0,
+            missed: Vec::new(),
+            explained: None,
+        })
+    };
+}
+
+/// Keeps what's been recorded for as long as a parse runs. Scopes nest, and
+/// ending the outermost one, even by unwinding, forgets everything.
+pub struct Scope {
+    _private: (),
+}
+
+impl Scope {
+    pub fn enter() -> Scope {
+        STATE.with(|state| state.borrow_mut().depth += 1);
+        Scope { _private: () }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.depth -= 1;
+
+            if state.depth == 0 {
+                state.missed.clear();
+                state.explained = None;
+            }
+        });
+    }
+}
+
+/// Records that `expected` could have appeared at the current position.
+/// Outside a [`Scope`] there's nothing to merge it into, so it's dropped.
+pub fn missed(expected: impl IntoIterator<Item = String>) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if state.depth > 0 {
+            state.missed.extend(expected);
+        }
+    });
+}
+
+/// Like [`missed`], with the expectations of a parse error that stopped
+/// without consuming anything. Errors that don't say what they expected
+/// aren't recorded.
+pub fn missed_error(error: &syn::Error) {
+    if let Some(expected) = expectations(error) {
+        missed(expected);
+    }
+}
+
+/// Runs a parse that may be abandoned, like one on a fork. If it fails, what
+/// it recorded is dropped along with it; its error still says what it
+/// expected.
+pub fn speculate<T>(parse: impl FnOnce() -> syn::Result<T>) -> syn::Result<T> {
+    let _scope = Scope::enter();
+    let before = recorded();
+    let result = attempt(parse);
+
+    if result.is_err() {
+        truncate(before);
+    }
+
+    result
+}
+
+/// Runs the parse of a group's content. Nothing recorded inside the group is
+/// about a position outside it, so all of it is dropped afterwards.
+pub fn isolate<T>(parse: impl FnOnce() -> T) -> T {
+    let _scope = Scope::enter();
+    let before = recorded();
+    let result = parse();
+    truncate(before);
+
+    result
+}
+
+/// Runs a parse whose error is checked with [`expectations`] afterwards, so
+/// that an explanation left by an earlier parse can't be mistaken for its.
+fn attempt<T>(parse: impl FnOnce() -> T) -> T {
+    STATE.with(|state| state.borrow_mut().explained = None);
+    parse()
+}
+
+fn recorded() -> usize {
+    STATE.with(|state| state.borrow().missed.len())
+}
+
+fn truncate(len: usize) {
+    STATE.with(|state| state.borrow_mut().missed.truncate(len));
+}
+
+fn forget(from: usize, to: usize) {
+    STATE.with(|state| {
+        let missed = &mut state.borrow_mut().missed;
+        let to = to.min(missed.len());
+        let from = from.min(to);
+        missed.drain(from..to);
+    })
+}
+
+fn take(from: usize) -> Vec<String> {
+    STATE.with(|state| {
+        let missed = &mut state.borrow_mut().missed;
+        let from = from.min(missed.len());
+        missed.drain(from..).collect()
+    })
+}
+
+/// Parses the parts of a sequence one after the other, merging what earlier
+/// empty parts looked for into the error of a part that fails.
+pub struct Sequence<'a> {
+    input: ParseStream<'a>,
+    mark: usize,
+    _scope: Scope,
+}
+
+impl<'a> Sequence<'a> {
+    pub fn new(input: ParseStream<'a>) -> Sequence<'a> {
+        let scope = Scope::enter();
+
+        Sequence {
+            input,
+            mark: recorded(),
+            _scope: scope,
+        }
+    }
+
+    pub fn parse<T>(&mut self) -> syn::Result<T>
+    where
+        T: Parse,
+    {
+        self.parse_with(T::parse)
+    }
+
+    pub fn parse_with<T>(
+        &mut self,
+        parse: impl FnOnce(ParseStream<'a>) -> syn::Result<T>,
+    ) -> syn::Result<T> {
+        let start = self.input.cursor();
+        let before = recorded();
+
+        match attempt(|| parse(self.input)) {
+            Ok(parsed) => {
+                // Whatever was missed before this part is behind us now.
+                if self.input.cursor() != start {
+                    forget(self.mark, before);
+                }
+                Ok(parsed)
+            }
+            Err(error) => {
+                let missed = take(self.mark);
+
+                if missed.is_empty() || self.input.cursor() != start {
+                    return Err(error);
+                }
+
+                match expectations(&error) {
+                    Some(expected) => Err(expected_one_of(
+                        error.span(),
+                        missed.into_iter().chain(expected),
+                    )),
+                    None => Err(error),
+                }
+            }
+        }
+    }
+}
+
+/// What a parse error expected, if it says. Errors built by
+/// [`expected_one_of`] give back the items they were built from. Otherwise
+/// only syn's own "expected ..." errors count, so that an unrelated message
+/// never ends up in an "expected one of" list.
+pub fn expectations(error: &syn::Error) -> Option<Vec<String>> {
+    let message = error.to_string();
+    let explained = STATE.with(|state| match &state.borrow().explained {
+        Some(explained) if explained.message == message => Some(explained.expected.clone()),
+        _ => None,
+    });
+
+    match explained {
+        Some(expected) => expected,
+        None => error
+            .clone()
+            .into_iter()
+            .map(|error| syn_expectations(&error.to_string()))
+            .collect::<Option<Vec<_>>>()
+            .map(|expected| expected.concat()),
+    }
+}
+
+/// syn's errors read "expected X", "expected X or Y" or "expected one of: X,
+/// Y, Z", prefixed with "unexpected end of input, " at the end of a stream.
+fn syn_expectations(message: &str) -> Option<Vec<String>> {
+    let message = message
+        .strip_prefix("unexpected end of input, ")
+        .unwrap_or(message);
+    let expected = message.strip_prefix("expected ")?;
+
+    let items: Vec<String> = match expected.strip_prefix("one of: ") {
+        Some(list) => list.split(", ").map(String::from).collect(),
+        None => expected.splitn(2, " or ").map(String::from).collect(),
+    };
+
+    Some(items)
+}
+
+/// "expected one of a, b, c", without duplicates.
+pub fn expected_one_of(span: Span, expected: impl IntoIterator<Item = String>) -> syn::Error {
+    let mut unique: Vec<String> = vec![];
+
+    for item in expected {
+        if !item.is_empty() && !unique.contains(&item) {
+            unique.push(item);
+        }
+    }
+
+    let error = match unique.len() {
+        0 => return unexplained(syn::Error::new(span, "no alternative matched")),
+        1 => syn::Error::new(span, format!("expected {}", unique[0])),
+        _ => syn::Error::new(span, format!("expected one of {}", unique.join(", "))),
+    };
+
+    explain(&error, Some(unique));
+    error
+}
+
+/// Marks `error` as not being about what could appear where it points, so it
+/// isn't merged into an "expected one of" list.
+pub fn unexplained(error: syn::Error) -> syn::Error {
+    explain(&error, None);
+    error
+}
+
+fn explain(error: &syn::Error, expected: Option<Vec<String>>) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if state.depth > 0 {
+            state.explained = Some(Explained {
+                message: error.to_string(),
+                expected,
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_parse::{
+        delimited::Bracketed,
+        optional::Optional,
+        pair::Pair,
+        repeat::Many,
+        separated::{Separated, SeparatedInline},
+    };
+    use crate::tools::quote::{quote, ToTokens};
+    use crate::tools::syn::{Ident, Token};
+    use crate::Syntax;
+
+    fn error<T: Parse>(input: crate::Tokens) -> String {
+        match syn::parse2::<T>(input) {
+            Ok(_) => panic!("expected a parse error"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[derive(Syntax)]
+    #[syntax(no_validate)]
+    struct Qualified {
+        constness: Optional<Token![const]>,
+        asyncness: Optional<Token![async]>,
+        unsafety: Optional<Token![unsafe]>,
+        fn_token: Token![fn],
+    }
+
+    #[test]
+    fn merges_missing_optional_parts_into_the_next_error() {
+        assert_eq!(
+            error::<Qualified>(quote!(pub)),
+            "expected one of `const`, `async`, `unsafe`, `fn`"
+        );
+        assert_eq!(
+            error::<Qualified>(quote!(const pub)),
+            "expected one of `async`, `unsafe`, `fn`"
+        );
+    }
+
+    #[derive(Syntax)]
+    #[syntax(no_validate)]
+    struct List {
+        list: Bracketed<Separated<Pair<Ident, Optional<Token![?]>>, Token![,]>>,
+        semi: Token![;],
+    }
+
+    #[test]
+    fn drops_what_was_missed_inside_a_group() {
+        assert_eq!(error::<List>(quote!([a, b] :)), "expected `;`");
+    }
+
+    #[derive(Syntax)]
+    struct Attributes {
+        attrs: Many<Pair<Token![@], Ident>>,
+        semi: Token![;],
+    }
+
+    #[test]
+    fn drops_what_a_failed_fork_expected_further_on() {
+        assert_eq!(error::<Attributes>(quote!(@a @ 1)), "expected `;`");
+        assert_eq!(
+            error::<Attributes>(quote!(@a :)),
+            "expected one of `@`, `;`"
+        );
+    }
+
+    #[derive(Syntax)]
+    struct Names {
+        names: SeparatedInline<Ident, Token![,]>,
+        semi: Token![;],
+    }
+
+    #[test]
+    fn drops_what_an_item_after_a_separator_expected() {
+        assert_eq!(error::<Names>(quote!(a, 1)), "expected `;`");
+        assert_eq!(error::<Names>(quote!(a :)), "expected one of `,`, `;`");
+    }
+
+    struct Unexpected;
+
+    impl Parse for Unexpected {
+        fn parse(input: ParseStream) -> syn::Result<Self> {
+            Err(input.error("unexpected token"))
+        }
+    }
+
+    impl ToTokens for Unexpected {
+        fn to_tokens(&self, _: &mut crate::Tokens) {}
+    }
+
+    #[derive(Syntax)]
+    #[syntax(no_validate)]
+    struct Custom {
+        question: Optional<Token![?]>,
+        rest: Unexpected,
+    }
+
+    #[test]
+    fn keeps_errors_that_dont_say_what_they_expected() {
+        assert_eq!(error::<Custom>(quote!(a)), "unexpected token");
+    }
+
+    #[test]
+    fn reads_what_syn_errors_expected() {
+        let expected = |message: &str| expectations(&syn::Error::new(Span::call_site(), message));
+
+        assert_eq!(expected("expected `;`"), Some(vec!["`;`".to_string()]));
+        assert_eq!(
+            expected("unexpected end of input, expected identifier"),
+            Some(vec!["identifier".to_string()])
+        );
+        assert_eq!(
+            expected("expected `a` or `b`"),
+            Some(vec!["`a`".to_string(), "`b`".to_string()])
+        );
+        assert_eq!(
+            expected("expected one of: `a`, `b`, `c`"),
+            Some(vec![
+                "`a`".to_string(),
+                "`b`".to_string(),
+                "`c`".to_string()
+            ])
+        );
+        assert_eq!(expected("unexpected token"), None);
+        assert_eq!(expected("something went wrong"), None);
+    }
+
+    #[test]
+    fn remembers_what_its_own_errors_expected() {
+        let _scope = Scope::enter();
+        let items = vec!["`a`".to_string(), "one, two".to_string()];
+        let error = expected_one_of(Span::call_site(), items.clone());
+
+        assert_eq!(expectations(&error), Some(items));
+        assert_eq!(expectations(&unexplained(error)), None);
+    }
+
+    #[test]
+    fn forgets_everything_when_the_outermost_scope_ends() {
+        let expected_b = || syn::Error::new(Span::call_site(), "expected `b`");
+
+        let error = {
+            let _outer = Scope::enter();
+
+            {
+                let _inner = Scope::enter();
+                missed(vec!["`a`".to_string()]);
+            }
+            assert_eq!(recorded(), 1);
+
+            let error = unexplained(expected_b());
+            assert_eq!(expectations(&error), None);
+            error
+        };
+
+        assert_eq!(recorded(), 0);
+        assert_eq!(expectations(&error), Some(vec!["`b`".to_string()]));
+    }
+
+    #[test]
+    fn ignores_what_an_earlier_parse_explained() {
+        let _scope = Scope::enter();
+        let earlier = unexplained(syn::Error::new(Span::call_site(), "expected `a`"));
+
+        let result: syn::Result<()> = speculate(|| Err(syn::Error::new(Span::call_site(), "oops")));
+        assert_eq!(expectations(&result.unwrap_err()), None);
+        assert_eq!(expectations(&earlier), Some(vec!["`a`".to_string()]));
+    }
+}
@@ -0,0 +1,533 @@
+//! Typed attribute arguments, like `#[my_attr(name = "x", retries = 3, skip,
+//! flags(a, b))]`.
+//!
+//! Derive [`FromMeta`] on a struct of named arguments to get a `Parse` impl
+//! for it. Field attributes:
+//!
+//! - `#[meta(default)]` uses `Default::default()` when the argument is missing
+//! - `#[meta(default = expr)]` uses `expr` when the argument is missing
+//! - `#[meta(rename = "name")]` changes the argument's name
+//!
+//! `bool` and `Option<T>` fields are optional, and a `bool` can be set by
+//! naming it on its own (`skip`).
+
+use std::collections::BTreeMap;
+
+use crate::tools::proc_macro2::{self, Span};
+use crate::tools::quote::{quote, ToTokens};
+use crate::tools::syn::{
+    self,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Token,
+};
+
+use crate::ast::Identifier;
+use crate::derive_parse::{delimited::Parenthesized, separated::Separated};
+use crate::error::{assertions::DiagnosticError, suggest, Errors, MacroResult};
+
+pub use macroscope_macro::FromMeta;
+
+/// A comma-separated list of arguments.
+pub type MetaArgs = Separated<MetaArg, Token![,]>;
+
+/// A single argument: `name`, `name = value`, `name(nested, ...)` or, inside a
+/// list, a bare literal.
+#[derive(Debug)]
+pub enum MetaArg {
+    Flag(syn::Ident),
+    Value(syn::Ident, Token![=], Box<syn::Expr>),
+    List(syn::Ident, Parenthesized<MetaArgs>),
+    Literal(syn::Lit),
+}
+
+impl MetaArg {
+    pub fn name(&self) -> Option<&syn::Ident> {
+        match self {
+            MetaArg::Flag(name) | MetaArg::Value(name, ..) | MetaArg::List(name, _) => Some(name),
+            MetaArg::Literal(_) => None,
+        }
+    }
+}
+
+impl Parse for MetaArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::Lit) {
+            return Ok(MetaArg::Literal(input.parse()?));
+        }
+
+        let name = syn::Ident::parse_any(input)?;
+
+        if input.peek(Token![=]) {
+            Ok(MetaArg::Value(name, input.parse()?, input.parse()?))
+        } else if input.peek(syn::token::Paren) {
+            Ok(MetaArg::List(name, input.parse()?))
+        } else {
+            Ok(MetaArg::Flag(name))
+        }
+    }
+}
+
+impl ToTokens for MetaArg {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            MetaArg::Flag(name) => tokens.extend(quote!(#name)),
+            MetaArg::Value(name, eq, value) => tokens.extend(quote!(#name #eq #value)),
+            MetaArg::List(name, list) => tokens.extend(quote!(#name #list)),
+            MetaArg::Literal(literal) => tokens.extend(quote!(#literal)),
+        }
+    }
+}
+
+/// A value that can be read from an attribute argument.
+pub trait FromMeta: Sized {
+    /// `name` on its own.
+    #[allow(clippy::result_large_err)]
+    fn from_flag(name: &syn::Ident) -> MacroResult<Self> {
+        Err(
+            DiagnosticError::spanned(name.span(), format!("Expected a value: `{} = ...`", name))
+                .into(),
+        )
+    }
+
+    /// `name = value`, or a bare literal inside a list.
+    #[allow(clippy::result_large_err)]
+    fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+        Err(DiagnosticError::spanned(expr.span(), "Unexpected value").into())
+    }
+
+    /// `name(nested, ...)`. `span` covers the whole list.
+    #[allow(clippy::result_large_err)]
+    fn from_list(span: Span, _args: &MetaArgs) -> MacroResult<Self> {
+        Err(DiagnosticError::spanned(span, "Unexpected list").into())
+    }
+
+    /// The value to use when the argument is left out, if it's optional.
+    fn missing() -> Option<Self> {
+        None
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn from_meta(arg: &MetaArg) -> MacroResult<Self> {
+        match arg {
+            MetaArg::Flag(name) => Self::from_flag(name),
+            MetaArg::Value(_, _, value) => Self::from_expr(value),
+            MetaArg::List(_, list) => Self::from_list(list.span(), list),
+            MetaArg::Literal(literal) => Self::from_expr(&syn::Expr::Lit(syn::ExprLit {
+                attrs: vec![],
+                lit: literal.clone(),
+            })),
+        }
+    }
+
+    /// Parses attribute arguments as a list, keeping the full diagnostics
+    /// that a `Parse` impl has to flatten into a `syn::Error`.
+    #[allow(clippy::result_large_err)]
+    fn from_tokens(tokens: proc_macro2::TokenStream) -> MacroResult<Self> {
+        let span = match tokens.clone().into_iter().next() {
+            Some(token) => token.span(),
+            None => Span::call_site(),
+        };
+        let args: MetaArgs = syn::parse2(tokens)?;
+
+        Self::from_list(span, &args)
+    }
+}
+
+/// Collects the named arguments of a list, reporting unknown and duplicate
+/// names. Used by the `FromMeta` derive.
+pub struct MetaFields<'a> {
+    span: Span,
+    args: BTreeMap<String, &'a MetaArg>,
+    errors: Errors,
+}
+
+impl<'a> MetaFields<'a> {
+    pub fn new(span: Span, args: &'a MetaArgs, known: &[&str]) -> MetaFields<'a> {
+        let mut fields = MetaFields {
+            span,
+            args: BTreeMap::new(),
+            errors: Errors::new(),
+        };
+
+        for arg in args.iter() {
+            let name = match arg.name() {
+                Some(name) => name,
+                None => {
+                    fields.errors.push(DiagnosticError::spanned(
+                        arg.span(),
+                        "Expected a named argument",
+                    ));
+                    continue;
+                }
+            };
+            let key = name.unraw().to_string();
+
+            if !known.contains(&key.as_str()) {
+                let name = Identifier::from(name.clone());
+                fields
+                    .errors
+                    .push(suggest::unknown("argument", &name, known));
+            } else if let Some(first) = fields.args.get(&key) {
+                let error =
+                    DiagnosticError::spanned(name.span(), format!("Duplicate argument `{}`", key))
+                        .note(arg_name(first), "first given here");
+                fields.errors.push(error);
+            } else {
+                fields.args.insert(key, arg);
+            }
+        }
+
+        fields
+    }
+
+    /// The argument `name`, which must be given unless its type has a
+    /// [`FromMeta::missing`] value.
+    pub fn required<T>(&mut self, name: &str) -> Option<T>
+    where
+        T: FromMeta,
+    {
+        match self.args.get(name) {
+            Some(arg) => self.parse(arg),
+            None => match T::missing() {
+                Some(value) => Some(value),
+                None => {
+                    self.errors.push(DiagnosticError::spanned(
+                        self.span,
+                        format!("Missing argument `{}`", name),
+                    ));
+                    None
+                }
+            },
+        }
+    }
+
+    /// The argument `name`, or `default()` if it isn't given.
+    pub fn or_else<T>(&mut self, name: &str, default: impl FnOnce() -> T) -> Option<T>
+    where
+        T: FromMeta,
+    {
+        match self.args.get(name) {
+            Some(arg) => self.parse(arg),
+            None => Some(default()),
+        }
+    }
+
+    /// Fails with every error found so far.
+    #[allow(clippy::result_large_err)]
+    pub fn finish(self) -> MacroResult<()> {
+        self.errors.finish()
+    }
+
+    fn parse<T>(&mut self, arg: &MetaArg) -> Option<T>
+    where
+        T: FromMeta,
+    {
+        self.errors.handle(T::from_meta(arg))
+    }
+}
+
+fn arg_name(arg: &MetaArg) -> Identifier {
+    Identifier::from(
+        arg.name()
+            .expect("only named arguments are collected")
+            .clone(),
+    )
+}
+
+/// The literal in `expr`, looking through any parentheses or groups.
+fn literal(expr: &syn::Expr) -> Option<&syn::Lit> {
+    match expr {
+        syn::Expr::Lit(lit) => Some(&lit.lit),
+        syn::Expr::Paren(paren) => literal(&paren.expr),
+        syn::Expr::Group(group) => literal(&group.expr),
+        _ => None,
+    }
+}
+
+fn expected(expr: &syn::Expr, expected: &str) -> DiagnosticError {
+    DiagnosticError::spanned(expr.span(), format!("Expected {}", expected))
+}
+
+/// Parses `expr` as `T`, also accepting `T` written inside a string literal.
+#[allow(clippy::result_large_err)]
+fn coerce<T>(expr: &syn::Expr, description: &str) -> MacroResult<T>
+where
+    T: Parse,
+{
+    let tokens = match literal(expr) {
+        Some(syn::Lit::Str(string)) => {
+            return string.parse().map_err(|_| {
+                let error = expected(expr, description);
+                error.info(format!("in `{}`", string.value())).into()
+            });
+        }
+        _ => expr.to_token_stream(),
+    };
+
+    syn::parse2(tokens).map_err(|_| expected(expr, description).into())
+}
+
+impl FromMeta for bool {
+    fn from_flag(_name: &syn::Ident) -> MacroResult<Self> {
+        Ok(true)
+    }
+
+    fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+        match literal(expr) {
+            Some(syn::Lit::Bool(value)) => Ok(value.value),
+            Some(syn::Lit::Str(value)) if value.value() == "true" => Ok(true),
+            Some(syn::Lit::Str(value)) if value.value() == "false" => Ok(false),
+            _ => Err(expected(expr, "`true` or `false`").into()),
+        }
+    }
+
+    fn missing() -> Option<Self> {
+        Some(false)
+    }
+}
+
+impl FromMeta for String {
+    fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+        match literal(expr) {
+            Some(syn::Lit::Str(value)) => Ok(value.value()),
+            _ => Err(expected(expr, "a string literal").into()),
+        }
+    }
+}
+
+impl FromMeta for char {
+    fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+        match literal(expr) {
+            Some(syn::Lit::Char(value)) => Ok(value.value()),
+            _ => Err(expected(expr, "a character literal").into()),
+        }
+    }
+}
+
+/// The number in `expr` as text, including a leading `-`. Integer literals are
+/// accepted where a float is expected, but not the other way around.
+fn number(expr: &syn::Expr, float: bool) -> Option<String> {
+    if let syn::Expr::Unary(syn::ExprUnary {
+        op: syn::UnOp::Neg(_),
+        expr,
+        ..
+    }) = expr
+    {
+        return number(expr, float)
+            .filter(|number| !number.starts_with('-'))
+            .map(|number| format!("-{}", number));
+    }
+
+    match literal(expr)? {
+        syn::Lit::Int(value) => Some(value.base10_digits().to_string()),
+        syn::Lit::Float(value) if float => Some(value.base10_digits().to_string()),
+        syn::Lit::Str(value) => Some(value.value()),
+        _ => None,
+    }
+}
+
+macro_rules! from_meta_number {
+    (@float Int) => { false };
+    (@float Float) => { true };
+
+    ($lit:ident, $description:tt => $($ty:ty),*) => {
+        $(
+            impl FromMeta for $ty {
+                fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+                    let parsed = number(expr, from_meta_number!(@float $lit))
+                        .and_then(|number| number.parse().ok());
+
+                    parsed.ok_or_else(|| {
+                        expected(expr, concat!($description, " (`", stringify!($ty), "`)")).into()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+from_meta_number!(Int, "an integer" => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+from_meta_number!(Float, "a number" => f32, f64);
+
+macro_rules! from_meta_syn {
+    ($($ty:ty => $description:tt),* $(,)?) => {
+        $(
+            impl FromMeta for $ty {
+                fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+                    coerce(expr, $description)
+                }
+            }
+        )*
+    };
+}
+
+from_meta_syn! {
+    syn::Path => "a path",
+    syn::Type => "a type",
+    syn::Expr => "an expression",
+    syn::LitStr => "a string literal",
+    syn::LitInt => "an integer literal",
+}
+
+impl FromMeta for syn::Ident {
+    fn from_flag(name: &syn::Ident) -> MacroResult<Self> {
+        Ok(name.clone())
+    }
+
+    fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+        coerce(expr, "an identifier")
+    }
+}
+
+impl FromMeta for syn::Lit {
+    fn from_expr(expr: &syn::Expr) -> MacroResult<Self> {
+        literal(expr)
+            .cloned()
+            .ok_or_else(|| expected(expr, "a literal").into())
+    }
+}
+
+impl<T> FromMeta for Option<T>
+where
+    T: FromMeta,
+{
+    fn from_meta(arg: &MetaArg) -> MacroResult<Self> {
+        T::from_meta(arg).map(Some)
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// `name(a, b, c)`, where each item is read with `T::from_meta`.
+impl<T> FromMeta for Vec<T>
+where
+    T: FromMeta,
+{
+    fn from_list(_span: Span, args: &MetaArgs) -> MacroResult<Self> {
+        let mut errors = Errors::new();
+        let items = args
+            .iter()
+            .filter_map(|arg| errors.handle(T::from_meta(arg)))
+            .collect();
+
+        errors.finish_with(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MacroError;
+
+    #[derive(Debug, FromMeta)]
+    struct Args {
+        name: String,
+        #[meta(default)]
+        retries: u8,
+        #[meta(default = 1.5)]
+        scale: f64,
+        #[meta(default)]
+        offset: i32,
+        #[meta(rename = "type")]
+        kind: Option<syn::Ident>,
+        skip: bool,
+        #[meta(default)]
+        flags: Vec<syn::Ident>,
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn parse(tokens: proc_macro2::TokenStream) -> MacroResult<Args> {
+        Args::from_tokens(tokens)
+    }
+
+    fn messages(error: MacroError) -> Vec<String> {
+        match error {
+            MacroError::Multiple(errors) => errors.iter().map(|e| e.to_string()).collect(),
+            error => vec![error.to_string()],
+        }
+    }
+
+    #[test]
+    fn fills_in_defaults() {
+        let args = parse(quote!(name = "x")).unwrap();
+
+        assert_eq!(args.name, "x");
+        assert_eq!(args.retries, 0);
+        assert_eq!(args.scale, 1.5);
+        assert_eq!(args.offset, 0);
+        assert!(args.kind.is_none());
+        assert!(!args.skip);
+        assert!(args.flags.is_empty());
+    }
+
+    #[test]
+    fn reads_every_kind_of_argument() {
+        let args = parse(quote!(
+            name = "x",
+            retries = 3,
+            scale = 2,
+            offset = -4,
+            type = Thing,
+            skip,
+            flags(a, b)
+        ))
+        .unwrap();
+
+        assert_eq!(args.retries, 3);
+        assert_eq!(args.scale, 2.0);
+        assert_eq!(args.offset, -4);
+        assert_eq!(args.kind.unwrap(), "Thing");
+        assert!(args.skip);
+        assert_eq!(args.flags, ["a", "b"]);
+    }
+
+    #[test]
+    fn reads_negative_numbers() {
+        let args = parse(quote!(name = "x", offset = -2147483648, scale = -0.5)).unwrap();
+
+        assert_eq!(args.offset, i32::MIN);
+        assert_eq!(args.scale, -0.5);
+
+        let error = parse(quote!(name = "x", retries = -1)).unwrap_err();
+        assert_eq!(messages(error), ["Expected an integer (`u8`)"]);
+
+        let error = parse(quote!(name = "x", offset = 1.5)).unwrap_err();
+        assert_eq!(messages(error), ["Expected an integer (`i32`)"]);
+    }
+
+    #[test]
+    fn reports_unknown_duplicate_and_missing_arguments() {
+        let error = parse(quote!(retries = 1, retries = 2, nmae = "x")).unwrap_err();
+
+        assert_eq!(
+            messages(error),
+            [
+                "Duplicate argument `retries`",
+                "Unknown argument `nmae`",
+                "Missing argument `name`",
+            ]
+        );
+    }
+
+    #[test]
+    fn points_duplicates_at_the_first_argument() {
+        let error = match parse(quote!(name = "x", name = "y")).unwrap_err() {
+            MacroError::Diagnostic(error) => error,
+            error => panic!("expected a single diagnostic, got {:?}", error),
+        };
+
+        let diagnostic = format!("{:?}", error);
+        assert!(diagnostic.contains("first given here"), "{}", diagnostic);
+    }
+
+    #[test]
+    fn rejects_values_for_lists_and_lists_for_values() {
+        let error = parse(quote!(name(x), flags = "a")).unwrap_err();
+
+        assert_eq!(messages(error), ["Unexpected list", "Unexpected value"]);
+    }
+}
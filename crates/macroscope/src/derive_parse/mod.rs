@@ -6,10 +6,14 @@ pub mod validate;
 
 pub mod choice;
 pub mod delimited;
+pub mod lookahead;
+pub mod meta;
 pub mod optional;
 pub mod pair;
+pub mod repeat;
 pub mod separated;
 pub mod tail;
+pub mod until;
 
 use crate::tools::proc_macro2;
 use crate::tools::syn;
@@ -49,8 +53,7 @@ pub fn found_crate_path(
 ) -> proc_macro2::TokenStream {
     let prefix = found_crate_prefix(found);
 
-    (quote! {
+    quote! {
         #prefix :: macros :: crates :: #quoted
-    })
-    .into()
+    }
 }
@@ -2,7 +2,7 @@ use crate::tools::proc_macro2;
 use crate::tools::quote::{quote, ToTokens};
 use crate::tools::syn::{self, parse::Parse};
 
-use crate::derive_parse::validate::Validate;
+use crate::derive_parse::{lookahead, validate::Validate};
 
 #[derive(Debug)]
 pub enum Optional<T>
@@ -13,24 +13,24 @@ where
     Missing,
 }
 
-impl<T> Into<Optional<T>> for Option<T>
+impl<T> From<Option<T>> for Optional<T>
 where
     T: Parse + Validate,
 {
-    fn into(self) -> Optional<T> {
-        match self {
+    fn from(option: Option<T>) -> Optional<T> {
+        match option {
             Some(present) => Optional::Present(present),
             None => Optional::Missing,
         }
     }
 }
 
-impl<T> Into<Option<T>> for Optional<T>
+impl<T> From<Optional<T>> for Option<T>
 where
     T: Parse + Validate,
 {
-    fn into(self) -> Option<T> {
-        match self {
+    fn from(optional: Optional<T>) -> Option<T> {
+        match optional {
             Optional::Present(present) => Some(present),
             Optional::Missing => None,
         }
@@ -57,8 +57,9 @@ where
         let is_valid = T::validate(&input);
 
         if is_valid {
-            Ok(Optional::Present(T::parse(&input)?))
+            Ok(Optional::Present(T::parse(input)?))
         } else {
+            lookahead::missed(T::expected());
             Ok(Optional::Missing)
         }
     }
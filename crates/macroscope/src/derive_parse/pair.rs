@@ -1,4 +1,4 @@
-use crate::derive_parse::validate::Validate;
+use crate::derive_parse::{lookahead::Sequence, validate::Validate};
 use crate::tools::proc_macro2;
 use crate::tools::quote::{quote, ToTokens};
 use crate::tools::syn::{self, parse::Parse};
@@ -23,6 +23,10 @@ where
     fn validate(stream: &syn::parse::ParseStream) -> bool {
         T::validate(stream)
     }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
 }
 
 impl<T, U> ToTokens for Prefixed<T, U>
@@ -42,8 +46,9 @@ where
     U: Parse,
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let prefix = T::parse(input)?;
-        let item = U::parse(input)?;
+        let mut sequence = Sequence::new(input);
+        let prefix = sequence.parse()?;
+        let item = sequence.parse()?;
 
         Ok(Prefixed { prefix, item })
     }
@@ -69,6 +74,10 @@ where
     fn validate(stream: &syn::parse::ParseStream) -> bool {
         T::validate(stream)
     }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
 }
 
 impl<T, U> Parse for Suffixed<T, U>
@@ -77,8 +86,9 @@ where
     U: Parse,
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let item = T::parse(input)?;
-        let suffix = U::parse(input)?;
+        let mut sequence = Sequence::new(input);
+        let item = sequence.parse()?;
+        let suffix = sequence.parse()?;
 
         Ok(Suffixed { item, suffix })
     }
@@ -113,6 +123,10 @@ where
     fn validate(stream: &syn::parse::ParseStream) -> bool {
         T::validate(stream)
     }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
 }
 
 impl<T, U> ToTokens for Pair<T, U>
@@ -132,8 +146,9 @@ where
     U: Parse,
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let left = T::parse(input)?;
-        let right = U::parse(input)?;
+        let mut sequence = Sequence::new(input);
+        let left = sequence.parse()?;
+        let right = sequence.parse()?;
 
         Ok(Pair { left, right })
     }
@@ -0,0 +1,185 @@
+use std::ops::Deref;
+
+use crate::tools::proc_macro2;
+use crate::tools::quote::ToTokens;
+use crate::tools::syn::{
+    self,
+    parse::{discouraged::Speculative, Parse, ParseStream},
+};
+
+use crate::derive_parse::{lookahead, validate::Validate};
+
+/// Zero or more `T`, with nothing between them.
+pub type Many<T> = Repeat<T, 0, { usize::MAX }>;
+
+/// One or more `T`, with nothing between them.
+pub type Many1<T> = Repeat<T, 1, { usize::MAX }>;
+
+/// Between `MIN` and `MAX` `T`s (inclusive), with nothing between them. Items
+/// are parsed speculatively, so the repetition stops at the first thing that
+/// doesn't parse as a `T`.
+#[derive(Debug, Clone)]
+pub struct Repeat<T, const MIN: usize, const MAX: usize> {
+    items: Vec<T>,
+}
+
+impl<T, const MIN: usize, const MAX: usize> Repeat<T, MIN, MAX> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Deref for Repeat<T, MIN, MAX> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> IntoIterator for Repeat<T, MIN, MAX> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T, const MIN: usize, const MAX: usize> IntoIterator for &'a Repeat<T, MIN, MAX> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Validate for Repeat<T, MIN, MAX>
+where
+    T: Validate,
+{
+    fn validate(stream: &ParseStream) -> bool {
+        T::validate(stream)
+    }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> ToTokens for Repeat<T, MIN, MAX>
+where
+    T: ToTokens,
+{
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        for item in &self.items {
+            item.to_tokens(tokens);
+        }
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Parse for Repeat<T, MIN, MAX>
+where
+    T: Parse,
+{
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut items = vec![];
+
+        let stopped = loop {
+            if input.is_empty() {
+                break None;
+            }
+
+            let fork = input.fork();
+
+            match lookahead::speculate(|| T::parse(&fork)) {
+                // An item that consumes nothing would repeat forever.
+                Ok(_) if fork.cursor() == input.cursor() => break None,
+                Ok(_) if items.len() == MAX => {
+                    return Err(lookahead::unexplained(syn::Error::new(
+                        input.span(),
+                        format!("expected at most {}, found more", count(MAX)),
+                    )));
+                }
+                Ok(item) => {
+                    items.push(item);
+                    input.advance_to(&fork);
+                }
+                // What a failed item expected only matters if it could
+                // have appeared right here.
+                Err(error) if fork.cursor() == input.cursor() => break Some(error),
+                Err(_) => break None,
+            }
+        };
+
+        if items.len() < MIN {
+            return Err(lookahead::unexplained(syn::Error::new(
+                input.span(),
+                format!("expected at least {}, found {}", count(MIN), items.len()),
+            )));
+        }
+
+        if let Some(stopped) = stopped {
+            lookahead::missed_error(&stopped);
+        }
+
+        Ok(Repeat { items })
+    }
+}
+
+fn count(n: usize) -> String {
+    match n {
+        1 => "1 item".to_string(),
+        n => format!("{} items", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_parse::pair::{Pair, Suffixed};
+    use crate::tools::quote::quote;
+    use crate::tools::syn::{Ident, Token};
+
+    type Items<const MIN: usize, const MAX: usize> = Repeat<Suffixed<Ident, Token![;]>, MIN, MAX>;
+
+    fn names<const MIN: usize, const MAX: usize>(input: crate::Tokens) -> syn::Result<Vec<String>> {
+        let items: Items<MIN, MAX> = syn::parse2(input)?;
+        Ok(items.iter().map(|item| item.item.to_string()).collect())
+    }
+
+    #[test]
+    fn parses_between_min_and_max_items() {
+        assert_eq!(names::<0, 2>(quote!()).unwrap(), Vec::<String>::new());
+        assert_eq!(names::<1, 2>(quote!(a;)).unwrap(), ["a"]);
+        assert_eq!(names::<1, 2>(quote!(a; b;)).unwrap(), ["a", "b"]);
+        assert_eq!(names::<2, 2>(quote!(a; b;)).unwrap(), ["a", "b"]);
+    }
+
+    #[test]
+    fn reports_too_few_items() {
+        let error = names::<2, 3>(quote!(a;)).unwrap_err();
+        assert_eq!(error.to_string(), "expected at least 2 items, found 1");
+
+        let error = syn::parse2::<Many1<Ident>>(quote!()).unwrap_err();
+        assert_eq!(error.to_string(), "expected at least 1 item, found 0");
+    }
+
+    #[test]
+    fn reports_too_many_items() {
+        let error = names::<0, 2>(quote!(a; b; c;)).unwrap_err();
+        assert_eq!(error.to_string(), "expected at most 2 items, found more");
+    }
+
+    #[test]
+    fn stops_at_the_first_item_that_does_not_parse() {
+        let items: Pair<Many<Suffixed<Ident, Token![;]>>, Token![!]> =
+            syn::parse2(quote!(a; b; !)).unwrap();
+        assert_eq!(items.left.len(), 2);
+
+        // `c` starts an item but doesn't finish it, so it's left unparsed.
+        let error = names::<0, 5>(quote!(a; b; c)).unwrap_err();
+        assert_eq!(error.to_string(), "unexpected token");
+    }
+}
@@ -1,11 +1,21 @@
-use std::{iter::FromIterator, marker::PhantomData};
+use std::{
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
 
 use syn::punctuated::{IntoPairs as SynIntoPairs, Pair as SynPair};
 
 use crate::tools::proc_macro2;
 use crate::tools::quote::{quote, ToTokens};
 use crate::tools::syn::{self, parse::Parse};
-use crate::tools::syn::{parse::ParseStream, punctuated::Punctuated};
+use crate::tools::syn::{
+    parse::{discouraged::Speculative, ParseStream},
+    punctuated::Punctuated,
+};
+
+use crate::derive_parse::{lookahead, validate::Validate};
+use crate::error::{Errors, MacroResult};
 
 pub trait ParseFrom {
     type Item: Parse;
@@ -24,6 +34,7 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct Pair<T, S> {
     inner: syn::punctuated::Pair<T, S>,
 }
@@ -89,11 +100,18 @@ where
 
 impl<T, S> std::fmt::Debug for Pair<T, S>
 where
-    T: Parse,
-    S: Parse,
+    T: std::fmt::Debug,
+    S: std::fmt::Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{{Pair newtype}}")
+        match &self.inner {
+            SynPair::Punctuated(item, separator) => f
+                .debug_tuple("Punctuated")
+                .field(item)
+                .field(separator)
+                .finish(),
+            SynPair::End(item) => f.debug_tuple("End").field(item).finish(),
+        }
     }
 }
 
@@ -113,6 +131,10 @@ where
         }
     }
 
+    pub fn into_punctuated(self) -> Punctuated<T, S> {
+        self.punctuated
+    }
+
     pub fn map<U>(self, mapper: impl Fn(&T) -> U) -> Separated<U, S> {
         let mapped = self.punctuated.pairs().map(|pair| match pair {
             syn::punctuated::Pair::Punctuated(item, punctuation) => {
@@ -126,6 +148,175 @@ where
             parse: PhantomData,
         }
     }
+
+    /// Like [`map`](Separated::map), reporting every item that failed to map.
+    #[allow(clippy::result_large_err)]
+    pub fn try_map<U>(self, mapper: impl Fn(&T) -> MacroResult<U>) -> MacroResult<Separated<U, S>> {
+        let mut errors = Errors::new();
+
+        let mapped = self.punctuated.pairs().filter_map(|pair| {
+            let item = errors.handle(mapper(pair.value()))?;
+
+            Some(match pair {
+                SynPair::Punctuated(_, punctuation) => {
+                    SynPair::Punctuated(item, punctuation.clone())
+                }
+                SynPair::End(_) => SynPair::End(item),
+            })
+        });
+        let punctuated = mapped.collect();
+
+        errors.finish_with(Separated {
+            punctuated,
+            parse: PhantomData,
+        })
+    }
+}
+
+impl<T, S, P> Separated<T, S, P> {
+    pub fn new() -> Self {
+        Separated {
+            punctuated: Punctuated::new(),
+            parse: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.punctuated.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.punctuated.is_empty()
+    }
+
+    pub fn iter(&self) -> syn::punctuated::Iter<'_, T> {
+        self.punctuated.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> syn::punctuated::IterMut<'_, T> {
+        self.punctuated.iter_mut()
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.punctuated.first()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.punctuated.last()
+    }
+
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.punctuated.first_mut()
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.punctuated.last_mut()
+    }
+
+    /// Whether the last item is followed by a separator.
+    pub fn trailing_separator(&self) -> bool {
+        self.punctuated.trailing_punct()
+    }
+
+    /// Appends `item`, adding a default separator after the current last item
+    /// if it doesn't have one.
+    pub fn push(&mut self, item: T)
+    where
+        S: Default,
+    {
+        self.punctuated.push(item)
+    }
+
+    /// Appends a separator after the last item.
+    ///
+    /// Panics if the list is empty or already ends with a separator.
+    pub fn push_punct(&mut self, separator: S) {
+        self.punctuated.push_punct(separator)
+    }
+
+    /// Inserts `item` at `index`, with default separators around it as needed.
+    ///
+    /// Panics if `index` is greater than the number of items.
+    pub fn insert(&mut self, index: usize, item: T)
+    where
+        S: Default,
+    {
+        self.punctuated.insert(index, item)
+    }
+
+    /// Removes and returns the item at `index` along with its separator. A
+    /// list without a trailing separator still has none afterwards.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "Separated::remove: index {} out of range for {} items",
+            index,
+            len
+        );
+
+        let trailing = self.punctuated.trailing_punct();
+        let mut pairs: Vec<_> = std::mem::take(&mut self.punctuated).into_pairs().collect();
+        let removed = pairs.remove(index).into_value();
+
+        if !trailing {
+            if let Some(last) = pairs.pop() {
+                pairs.push(SynPair::End(last.into_value()));
+            }
+        }
+
+        self.punctuated = pairs.into_iter().collect();
+        removed
+    }
+}
+
+impl<T, S, P> Default for Separated<T, S, P> {
+    fn default() -> Self {
+        Separated::new()
+    }
+}
+
+impl<T, S, P> Index<usize> for Separated<T, S, P> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.punctuated[index]
+    }
+}
+
+impl<T, S, P> IndexMut<usize> for Separated<T, S, P> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.punctuated[index]
+    }
+}
+
+impl<T, S, P> IntoIterator for Separated<T, S, P> {
+    type Item = T;
+    type IntoIter = syn::punctuated::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.punctuated.into_iter()
+    }
+}
+
+impl<'a, T, S, P> IntoIterator for &'a Separated<T, S, P> {
+    type Item = &'a T;
+    type IntoIter = syn::punctuated::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.punctuated.iter()
+    }
+}
+
+impl<'a, T, S, P> IntoIterator for &'a mut Separated<T, S, P> {
+    type Item = &'a mut T;
+    type IntoIter = syn::punctuated::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.punctuated.iter_mut()
+    }
 }
 
 pub struct IntoSeparatedPairs<T, S> {
@@ -195,7 +386,7 @@ where
     P: ParseFrom<Item = T>,
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let punctuated = Punctuated::parse_terminated_with(&input, |stream| P::parse_from(stream))?;
+        let punctuated = Punctuated::parse_terminated_with(input, |stream| P::parse_from(stream))?;
 
         Ok(Separated {
             punctuated,
@@ -203,3 +394,353 @@ where
         })
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trailing {
+    Allowed,
+    Forbidden,
+    Required,
+}
+
+/// Parses the whole stream as items separated by `S`, checking the rules that
+/// `Punctuated::parse_terminated` doesn't.
+fn parse_terminated<T, S>(
+    input: ParseStream,
+    non_empty: bool,
+    trailing: Trailing,
+) -> syn::Result<Separated<T, S>>
+where
+    T: Parse,
+    S: Parse,
+{
+    let mut punctuated = Punctuated::new();
+    let mut last_item = input.span();
+    let mut last_separator = None;
+
+    while !input.is_empty() {
+        last_item = input.span();
+        punctuated.push_value(T::parse(input)?);
+        last_separator = None;
+
+        if input.is_empty() {
+            break;
+        }
+
+        last_separator = Some(input.span());
+        punctuated.push_punct(S::parse(input)?);
+    }
+
+    if non_empty && punctuated.is_empty() {
+        return Err(lookahead::unexplained(syn::Error::new(
+            input.span(),
+            "expected at least one item",
+        )));
+    }
+
+    match (trailing, last_separator) {
+        (Trailing::Forbidden, Some(separator)) => {
+            Err(syn::Error::new(separator, "unexpected trailing separator"))
+        }
+        (Trailing::Required, None) if !punctuated.is_empty() => Err(syn::Error::new(
+            last_item,
+            "expected a separator after the last item",
+        )),
+        _ => Ok(Separated::from(punctuated)),
+    }
+}
+
+macro_rules! separated_variant {
+    ($(#[$meta:meta])* $name:ident => parse_terminated($non_empty:expr, $trailing:expr)) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name<T, S> {
+            inner: Separated<T, S>,
+        }
+
+        transparent_wrapper!($name<T, S> => self.inner as Separated<T, S>);
+
+        impl<T, S> From<$name<T, S>> for Separated<T, S> {
+            fn from(variant: $name<T, S>) -> Separated<T, S> {
+                variant.inner
+            }
+        }
+
+        impl<T, S> ToTokens for $name<T, S>
+        where
+            T: ToTokens + Parse,
+            S: ToTokens + Parse,
+        {
+            fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+                self.inner.to_tokens(tokens)
+            }
+        }
+
+        impl<T, S> Parse for $name<T, S>
+        where
+            T: Parse,
+            S: Parse,
+        {
+            fn parse(input: ParseStream) -> syn::Result<Self> {
+                Ok($name {
+                    inner: parse_terminated(input, $non_empty, $trailing)?,
+                })
+            }
+        }
+    };
+}
+
+separated_variant!(
+    /// Like [`Separated`], but with at least one item.
+    SeparatedNonEmpty => parse_terminated(true, Trailing::Allowed)
+);
+
+separated_variant!(
+    /// Like [`Separated`], but without a separator after the last item.
+    SeparatedNoTrailing => parse_terminated(false, Trailing::Forbidden)
+);
+
+separated_variant!(
+    /// Like [`Separated`], but with a separator after every item, including
+    /// the last.
+    SeparatedRequireTrailing => parse_terminated(false, Trailing::Required)
+);
+
+impl<T, S> Validate for SeparatedNonEmpty<T, S>
+where
+    T: Validate,
+{
+    fn validate(stream: &ParseStream) -> bool {
+        T::validate(stream)
+    }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
+}
+
+/// One or more items separated by `S`, which stops at the first separator
+/// that isn't followed by an item, leaving that separator unparsed. Unlike
+/// [`Separated`], it doesn't need to be at the end of the stream, so it can
+/// appear in the middle of a grammar.
+#[derive(Debug, Clone)]
+pub struct SeparatedInline<T, S> {
+    inner: Separated<T, S>,
+}
+
+transparent_wrapper!(SeparatedInline<T, S> => self.inner as Separated<T, S>);
+
+impl<T, S> From<SeparatedInline<T, S>> for Separated<T, S> {
+    fn from(variant: SeparatedInline<T, S>) -> Separated<T, S> {
+        variant.inner
+    }
+}
+
+impl<T, S> Validate for SeparatedInline<T, S>
+where
+    T: Validate,
+{
+    fn validate(stream: &ParseStream) -> bool {
+        T::validate(stream)
+    }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
+}
+
+impl<T, S> ToTokens for SeparatedInline<T, S>
+where
+    T: ToTokens + Parse,
+    S: ToTokens + Parse,
+{
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.inner.to_tokens(tokens)
+    }
+}
+
+impl<T, S> Parse for SeparatedInline<T, S>
+where
+    T: Parse,
+    S: Parse,
+{
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut punctuated = Punctuated::new();
+        punctuated.push_value(T::parse(input)?);
+
+        loop {
+            let fork = input.fork();
+
+            let separator = match lookahead::speculate(|| S::parse(&fork)) {
+                Ok(separator) => separator,
+                Err(error) => {
+                    if fork.cursor() == input.cursor() {
+                        lookahead::missed_error(&error);
+                    }
+                    break;
+                }
+            };
+
+            // An item that fails after a separator leaves the separator
+            // unparsed, so what the item expected is beside the point.
+            let item = match lookahead::speculate(|| T::parse(&fork)) {
+                Ok(item) => item,
+                Err(_) => break,
+            };
+
+            input.advance_to(&fork);
+            punctuated.push_punct(separator);
+            punctuated.push_value(item);
+        }
+
+        Ok(SeparatedInline {
+            inner: Separated::from(punctuated),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_parse::delimited::Bracketed;
+    use crate::error::MacroError;
+    use crate::testing::TokenDiff;
+    use crate::tools::proc_macro2::TokenStream;
+    use crate::tools::syn::{Ident, LitInt, Token};
+
+    type List = Separated<Ident, Token![,]>;
+
+    fn list(input: TokenStream) -> List {
+        syn::parse2(input).unwrap()
+    }
+
+    fn assert_tokens(list: &impl ToTokens, expected: TokenStream) {
+        let diff = TokenDiff::new(&expected, &list.to_token_stream());
+        assert!(diff.is_same(), "{}", diff);
+    }
+
+    #[test]
+    fn removes_an_item_with_its_separator() {
+        let mut items = list(quote!(a, b, c));
+        assert_eq!(items.remove(1), "b");
+        assert_tokens(&items, quote!(a, c));
+
+        let mut items = list(quote!(a, b, c));
+        assert_eq!(items.remove(0), "a");
+        assert_tokens(&items, quote!(b, c));
+    }
+
+    #[test]
+    fn removing_the_last_item_keeps_the_trailing_separator_as_it_was() {
+        let mut items = list(quote!(a, b, c));
+        assert_eq!(items.remove(2), "c");
+        assert_tokens(&items, quote!(a, b));
+        assert!(!items.trailing_separator());
+
+        let mut items = list(quote!(a, b, c,));
+        assert_eq!(items.remove(2), "c");
+        assert_tokens(&items, quote!(a, b,));
+        assert!(items.trailing_separator());
+
+        let mut items = list(quote!(a));
+        assert_eq!(items.remove(0), "a");
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "index 2 out of range for 2 items")]
+    fn removing_past_the_end_panics() {
+        list(quote!(a, b)).remove(2);
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn number(ident: &Ident) -> MacroResult<LitInt> {
+        let name = ident.to_string();
+
+        match name.strip_prefix('n') {
+            Some(digits) => Ok(LitInt::new(digits, ident.span())),
+            None => Err(MacroError::compile_error(ident.span(), name)),
+        }
+    }
+
+    #[test]
+    fn try_map_keeps_the_separators() {
+        let mapped = list(quote!(n1, n2,)).try_map(number).unwrap();
+        assert_tokens(&mapped, quote!(1, 2,));
+    }
+
+    #[test]
+    fn try_map_reports_every_failed_item() {
+        let error = list(quote!(n1, a, n3, b)).try_map(number).unwrap_err();
+
+        let mut errors = Errors::new();
+        errors.push(error);
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+
+        assert_eq!(messages, ["a", "b"]);
+    }
+
+    #[test]
+    fn try_map_returns_a_single_error_as_is() {
+        let error = list(quote!(n1, a)).try_map(number).unwrap_err();
+        assert!(matches!(error, MacroError::Custom(_)));
+    }
+
+    /// The error from parsing `input`, with the column it points at.
+    fn error<T: Parse>(input: &str) -> (String, usize) {
+        let tokens: TokenStream = input.parse().unwrap();
+
+        match syn::parse2::<T>(tokens) {
+            Ok(_) => panic!("expected {:?} not to parse", input),
+            Err(error) => (error.to_string(), error.span().start().column),
+        }
+    }
+
+    fn parses<T: Parse>(input: &str) -> bool {
+        syn::parse_str::<T>(input).is_ok()
+    }
+
+    #[test]
+    fn requires_at_least_one_item() {
+        type NonEmpty = SeparatedNonEmpty<Ident, Token![,]>;
+
+        assert!(parses::<NonEmpty>("a"));
+        assert!(parses::<NonEmpty>("a, b,"));
+        assert_eq!(
+            error::<Bracketed<NonEmpty>>("[ ]"),
+            ("expected at least one item".to_string(), 2)
+        );
+        assert_eq!(
+            error::<NonEmpty>("a, 1"),
+            ("expected identifier".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn forbids_a_trailing_separator() {
+        type NoTrailing = SeparatedNoTrailing<Ident, Token![,]>;
+
+        assert!(parses::<NoTrailing>(""));
+        assert!(parses::<NoTrailing>("a, b"));
+        assert_eq!(
+            error::<NoTrailing>("a, b,"),
+            ("unexpected trailing separator".to_string(), 4)
+        );
+        assert_eq!(error::<NoTrailing>("a b"), ("expected `,`".to_string(), 2));
+    }
+
+    #[test]
+    fn requires_a_trailing_separator() {
+        type RequireTrailing = SeparatedRequireTrailing<Ident, Token![;]>;
+
+        assert!(parses::<RequireTrailing>(""));
+        assert!(parses::<RequireTrailing>("a; b;"));
+        assert_eq!(
+            error::<RequireTrailing>("a; b"),
+            ("expected a separator after the last item".to_string(), 3)
+        );
+        assert_eq!(
+            error::<RequireTrailing>("a;;"),
+            ("expected identifier".to_string(), 2)
+        );
+    }
+}
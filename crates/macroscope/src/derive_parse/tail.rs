@@ -5,7 +5,11 @@ use crate::tools::syn::{
     parse::{discouraged::Speculative, Parse},
 };
 
-use crate::derive_parse::{validate::Validate, wrapper::HasParts};
+use crate::derive_parse::{
+    lookahead::{self, Sequence},
+    validate::Validate,
+    wrapper::HasParts,
+};
 
 #[derive(Debug)]
 pub struct ListWithTail<T, U>
@@ -42,6 +46,12 @@ where
     fn validate(stream: &syn::parse::ParseStream) -> bool {
         T::validate(stream) || U::validate(stream)
     }
+
+    fn expected() -> Vec<String> {
+        let mut expected = T::expected();
+        expected.extend(U::expected());
+        expected
+    }
 }
 
 impl<T, U> ToTokens for ListWithTail<T, U>
@@ -62,23 +72,27 @@ where
     U: Parse,
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut sequence = Sequence::new(input);
         let mut head = vec![];
 
         loop {
             let fork = input.fork();
 
-            match T::parse(&fork) {
+            match lookahead::speculate(|| T::parse(&fork)) {
                 Ok(part) => {
                     if fork.is_empty() {
-                        let tail = U::parse(&input)?;
+                        let tail = sequence.parse()?;
                         return Ok(ListWithTail { head, tail });
                     } else {
                         head.push(part);
                         input.advance_to(&fork);
                     }
                 }
-                Err(_) => {
-                    let tail = U::parse(&input)?;
+                Err(error) => {
+                    if fork.cursor() == input.cursor() {
+                        lookahead::missed_error(&error);
+                    }
+                    let tail = sequence.parse()?;
                     return Ok(ListWithTail { head, tail });
                 }
             }
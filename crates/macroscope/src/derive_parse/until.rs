@@ -0,0 +1,204 @@
+use std::marker::PhantomData;
+
+use crate::tools::proc_macro2::{self, TokenStream, TokenTree};
+use crate::tools::quote::ToTokens;
+use crate::tools::syn::{
+    self,
+    parse::{Parse, ParseStream},
+};
+
+use crate::derive_parse::validate::Validate;
+
+/// Arbitrary tokens up to, but not including, the first `Term` (or the end of
+/// the input). Delimited groups are taken whole, so a `Term` inside brackets
+/// doesn't end the run.
+#[derive(Debug, Clone)]
+pub struct Until<Term> {
+    tokens: TokenStream,
+    terminator: PhantomData<Term>,
+}
+
+impl<Term> Until<Term> {
+    pub fn tokens(&self) -> &TokenStream {
+        &self.tokens
+    }
+
+    pub fn into_tokens(self) -> TokenStream {
+        self.tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Parses the captured tokens as a `T`, which has to use all of them.
+    pub fn parse_as<T>(&self) -> syn::Result<T>
+    where
+        T: Parse,
+    {
+        syn::parse2(self.tokens.clone())
+    }
+}
+
+impl<Term> ToTokens for Until<Term> {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.tokens.to_tokens(tokens)
+    }
+}
+
+impl<Term> Parse for Until<Term>
+where
+    Term: Validate,
+{
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut tokens = TokenStream::new();
+
+        while !input.is_empty() && !Term::validate(&input) {
+            tokens.extend(Some(input.parse::<TokenTree>()?));
+        }
+
+        Ok(Until {
+            tokens,
+            terminator: PhantomData,
+        })
+    }
+}
+
+/// A `T` followed by `Term`, where `T` is parsed from only the tokens before
+/// the first `Term`. Unlike `Suffixed`, a greedy `T` like `syn::Expr` can't
+/// run past the terminator.
+#[derive(Debug)]
+pub struct Terminated<T, Term>
+where
+    T: Parse,
+    Term: Parse,
+{
+    pub item: T,
+    pub terminator: Term,
+}
+
+transparent_wrapper!(Terminated<T, Term> where { T: Parse, Term: Parse } => self.item as T);
+
+impl<T, Term> Validate for Terminated<T, Term>
+where
+    T: Parse + Validate,
+    Term: Parse,
+{
+    fn validate(stream: &ParseStream) -> bool {
+        T::validate(stream)
+    }
+
+    fn expected() -> Vec<String> {
+        T::expected()
+    }
+}
+
+impl<T, Term> ToTokens for Terminated<T, Term>
+where
+    T: Parse + ToTokens,
+    Term: Parse + ToTokens,
+{
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.item.to_tokens(tokens);
+        self.terminator.to_tokens(tokens);
+    }
+}
+
+impl<T, Term> Parse for Terminated<T, Term>
+where
+    T: Parse,
+    Term: Parse + Validate,
+{
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let run: Until<Term> = input.parse()?;
+
+        // Running out of captured tokens is reported at the terminator rather
+        // than at the macro call site.
+        let item = run
+            .parse_as()
+            .map_err(|error| respan_end_of_input(error, input.span()))?;
+        let terminator = input.parse()?;
+
+        Ok(Terminated { item, terminator })
+    }
+}
+
+fn respan_end_of_input(error: syn::Error, end: proc_macro2::Span) -> syn::Error {
+    let mut errors = error.into_iter().map(|error| {
+        let message = error.to_string();
+
+        if message.starts_with("unexpected end of input") {
+            syn::Error::new(end, message)
+        } else {
+            error
+        }
+    });
+
+    let first = errors
+        .next()
+        .expect("a syn::Error has at least one message");
+    errors.fold(first, |mut combined, error| {
+        combined.combine(error);
+        combined
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_parse::pair::Pair;
+    use crate::testing::tokens_eq;
+    use crate::tools::quote::quote;
+    use crate::tools::syn::Token;
+
+    type Statement = Pair<Until<Token![;]>, Token![;]>;
+
+    #[test]
+    fn stops_at_the_first_terminator() {
+        let statement: Statement = syn::parse2(quote!(a + b;)).unwrap();
+        assert!(tokens_eq(statement.left.tokens(), &quote!(a + b)));
+
+        let statements: Pair<Statement, Statement> = syn::parse2(quote!(a; b;)).unwrap();
+        assert!(tokens_eq(statements.left.left.tokens(), &quote!(a)));
+        assert!(tokens_eq(statements.right.left.tokens(), &quote!(b)));
+    }
+
+    #[test]
+    fn takes_terminators_inside_groups_whole() {
+        let statement: Statement = syn::parse2(quote!(f(a; b) [c;] { d; };)).unwrap();
+        assert!(tokens_eq(
+            statement.left.tokens(),
+            &quote!(f(a; b) [c;] { d; })
+        ));
+    }
+
+    #[test]
+    fn runs_to_the_end_without_a_terminator() {
+        let run: Until<Token![;]> = syn::parse2(quote!(a (b;) c)).unwrap();
+        assert!(tokens_eq(run.tokens(), &quote!(a (b;) c)));
+
+        let run: Until<Token![;]> = syn::parse2(quote!()).unwrap();
+        assert!(run.is_empty());
+    }
+
+    #[test]
+    fn terminated_keeps_greedy_items_before_the_terminator() {
+        let terminated: Terminated<syn::Expr, Token![=>]> = syn::parse2(quote!(a + b =>)).unwrap();
+        assert!(tokens_eq(
+            &terminated.item.to_token_stream(),
+            &quote!(a + b)
+        ));
+
+        let error = syn::parse2::<Terminated<syn::Expr, Token![=>]>>(quote!(a + =>)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unexpected end of input, expected expression"
+        );
+    }
+
+    #[test]
+    fn terminated_requires_the_terminator() {
+        let error = syn::parse2::<Terminated<syn::Ident, Token![;]>>(quote!(a)).unwrap_err();
+        assert_eq!(error.to_string(), "expected `;`");
+    }
+}
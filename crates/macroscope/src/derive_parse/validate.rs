@@ -3,8 +3,16 @@ use crate::tools::syn::{
     token::Token,
 };
 
+use crate::derive_parse::lookahead;
+
 pub trait Validate {
     fn validate(stream: &ParseStream) -> bool;
+
+    /// What `validate` peeks for, like `` `async` ``, for "expected one of"
+    /// errors.
+    fn expected() -> Vec<String> {
+        vec![]
+    }
 }
 
 impl<T> Validate for T
@@ -14,13 +22,17 @@ where
     fn validate(stream: &ParseStream) -> bool {
         T::peek(stream.cursor())
     }
+
+    fn expected() -> Vec<String> {
+        vec![T::display().to_string()]
+    }
 }
 
 pub trait SpeculativeValidate: Parse {
     fn speculative_validate(stream: &ParseStream) -> Option<Self> {
         let fork = stream.fork();
 
-        match Self::parse(&fork) {
+        match lookahead::speculate(|| Self::parse(&fork)) {
             Ok(parsed) => {
                 stream.advance_to(&fork);
                 Some(parsed)
@@ -39,7 +51,7 @@ macro_rules! try_parse {
 
         let fork = $input.fork();
 
-        match <$ty>::parse(&fork) {
+        match $crate::derive_parse::lookahead::speculate(|| <$ty>::parse(&fork)) {
             Ok(parsed) => {
                 $input.advance_to(&fork);
                 Some(parsed)
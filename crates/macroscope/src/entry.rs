@@ -0,0 +1,45 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{error::assertions::DiagnosticError, impl_prelude::*};
+
+extern crate proc_macro;
+
+/// Runs the body of a proc macro, converting both errors and panics into
+/// compile errors. Without this, a panic (for example from
+/// [`find_crate`](crate::find_crate)) only tells the user that the proc macro
+/// panicked, without pointing at the macro call.
+///
+/// ```ignore
+/// #[proc_macro_attribute]
+/// pub fn my_attr(args: TokenStream, item: TokenStream) -> TokenStream {
+///     macroscope::entry_point(|| expand(args.into(), item.into()))
+/// }
+/// ```
+pub fn entry_point<T>(body: impl FnOnce() -> MacroResult<T>) -> proc_macro::TokenStream
+where
+    T: ProcMacroTokens,
+{
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(tokens)) => tokens.into_std_tokens(),
+        Ok(Err(error)) => error.to_compile_error().into(),
+        Err(payload) => MacroError::from(panic_error(payload))
+            .to_compile_error()
+            .into(),
+    }
+}
+
+fn panic_error(payload: Box<dyn std::any::Any + Send>) -> DiagnosticError {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+
+    DiagnosticError::spanned(
+        Span::call_site(),
+        format!("proc macro panicked: {}", message),
+    )
+    .info("the panic happened while expanding this macro")
+}
@@ -4,6 +4,9 @@ use macroscope_utils::tools::proc_macro_error::Level;
 
 use crate::impl_prelude::*;
 
+#[cfg(feature = "nightly")]
+extern crate proc_macro;
+
 #[derive(Debug, Clone, thiserror::Error)]
 #[error("{}", .message)]
 pub struct DiagnosticError {
@@ -13,17 +16,57 @@ pub struct DiagnosticError {
     helps: Vec<(Span, String)>,
     notes: Vec<(Span, String)>,
     infos: Vec<String>,
+    suggestions: Vec<Suggestion>,
+}
+
+/// A proposed fix: replace the code at `span` with `replacement`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    span: Span,
+    replacement: Tokens,
+    message: String,
+}
+
+impl Suggestion {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn replacement(&self) -> &Tokens {
+        &self.replacement
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let replacement = format!("`{}`", self.replacement);
+
+        if self.replacement.is_empty() || self.message.contains(&replacement) {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.message, replacement)
+        }
+    }
 }
 
 impl DiagnosticError {
     pub fn new(node: &impl AstNode, message: impl Into<String>) -> DiagnosticError {
+        DiagnosticError::spanned(node.span(), message)
+    }
+
+    pub fn spanned(span: Span, message: impl Into<String>) -> DiagnosticError {
         DiagnosticError {
             message: message.into(),
-            span: node.span(),
+            span,
             errors: vec![],
             helps: vec![],
             notes: vec![],
             infos: vec![],
+            suggestions: vec![],
         }
     }
 
@@ -59,6 +102,36 @@ impl DiagnosticError {
         self
     }
 
+    /// Suggests replacing `node` with `replacement`. Empty replacement tokens
+    /// suggest removing the node.
+    pub fn suggest(
+        self,
+        node: impl AstNode,
+        replacement: impl ToTokens,
+        message: impl Into<String>,
+    ) -> Self {
+        self.suggest_at(node.span(), replacement, message)
+    }
+
+    /// Like [`DiagnosticError::suggest`], for code that isn't a single node.
+    pub fn suggest_at(
+        mut self,
+        span: Span,
+        replacement: impl ToTokens,
+        message: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            span,
+            replacement: replacement.to_token_stream(),
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+
     pub fn message(mut self, message: impl Into<String>) -> Self {
         let original = self.message;
         self.message = message.into();
@@ -76,6 +149,10 @@ impl DiagnosticError {
             diagnostic = diagnostic.span_help(span, message);
         }
 
+        for suggestion in self.suggestions {
+            diagnostic = diagnostic.span_help(suggestion.span, suggestion.to_string());
+        }
+
         for (span, message) in self.notes {
             diagnostic = diagnostic.span_note(span, message);
         }
@@ -88,12 +165,91 @@ impl DiagnosticError {
     }
 }
 
+#[cfg(not(feature = "nightly"))]
 impl CompileError for DiagnosticError {
     fn into_compile_error(self) -> Tokens {
         self.into_diagnostic().to_token_stream()
     }
 }
 
+/// With the `nightly` feature, diagnostics are emitted through the compiler's
+/// native diagnostic API instead of `compile_error!`, so helps and notes are
+/// attached to their spans. The returned tokens are empty.
+///
+/// Emitting is a side effect: every call reports the error again, so convert
+/// each error once. Compiler spans only exist while a proc macro runs, so
+/// this panics anywhere else, e.g. in unit tests. Use
+/// [`MacroError::to_error_tokens`] where either is a problem.
+#[cfg(feature = "nightly")]
+impl CompileError for DiagnosticError {
+    fn into_compile_error(self) -> Tokens {
+        self.into_native_diagnostic().emit();
+        Tokens::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl DiagnosticError {
+    /// Converts this error into a `proc_macro::Diagnostic`. Panics outside of
+    /// a proc macro.
+    pub fn into_native_diagnostic(self) -> proc_macro::Diagnostic {
+        let mut diagnostic = proc_macro::Diagnostic::spanned(
+            self.span.unwrap(),
+            proc_macro::Level::Error,
+            self.message,
+        );
+
+        for (span, message) in self.errors {
+            diagnostic = diagnostic.span_error(span.unwrap(), message);
+        }
+
+        for (span, message) in self.helps {
+            diagnostic = diagnostic.span_help(span.unwrap(), message);
+        }
+
+        for suggestion in self.suggestions {
+            diagnostic = diagnostic.span_help(suggestion.span.unwrap(), suggestion.to_string());
+        }
+
+        for (span, message) in self.notes {
+            diagnostic = diagnostic.span_note(span.unwrap(), message);
+        }
+
+        for message in self.infos {
+            diagnostic = diagnostic.note(message);
+        }
+
+        diagnostic
+    }
+}
+
+/// `syn::Error` only carries a message per span, so helps, suggestions and
+/// notes are folded into the message.
+impl From<DiagnosticError> for syn::Error {
+    fn from(diagnostic: DiagnosticError) -> syn::Error {
+        let mut message = diagnostic.message;
+
+        let helps = diagnostic.helps.into_iter().map(|(_, help)| help);
+        let suggestions = diagnostic.suggestions.iter().map(|s| s.to_string());
+        for help in helps.chain(suggestions) {
+            message.push_str(&format!("\n  = help: {}", help));
+        }
+
+        let notes = diagnostic.notes.into_iter().map(|(_, note)| note);
+        for note in notes.chain(diagnostic.infos) {
+            message.push_str(&format!("\n  = note: {}", note));
+        }
+
+        let mut error = syn::Error::new(diagnostic.span, message);
+
+        for (span, message) in diagnostic.errors {
+            error.combine(syn::Error::new(span, message));
+        }
+
+        error
+    }
+}
+
 pub struct AssertionFailureExpected {
     expected: String,
 }
@@ -149,6 +305,15 @@ impl AssertionFailureProperty {
         AssertionFailurePropertyExpectation {
             property: self.property,
             expected: expected.into(),
+            negated: false,
+        }
+    }
+
+    pub fn to_not_be(self, expected: impl Into<String>) -> AssertionFailurePropertyExpectation {
+        AssertionFailurePropertyExpectation {
+            property: self.property,
+            expected: expected.into(),
+            negated: true,
         }
     }
 }
@@ -157,13 +322,16 @@ impl AssertionFailureProperty {
 pub struct AssertionFailurePropertyExpectation {
     property: Property,
     expected: String,
+    negated: bool,
 }
 
 impl AssertionFailurePropertyExpectation {
     pub fn but_was(self, node: &impl AstNode) -> DiagnosticError {
+        let to_be = if self.negated { "to not be" } else { "to be" };
+
         DiagnosticError::new(
             node,
-            format!("Expected {} to be {}", self.property, self.expected),
+            format!("Expected {} {} {}", self.property, to_be, self.expected),
         )
     }
 }
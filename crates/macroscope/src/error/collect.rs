@@ -0,0 +1,161 @@
+use std::iter::FromIterator;
+
+use crate::impl_prelude::*;
+
+/// Collects errors so that they can be reported together, rather than
+/// returning early at the first one. Parsing many fields or variants can push
+/// every failure here and report them all once it's done.
+#[derive(Debug, Default, thiserror::Error)]
+#[error("{}", .errors.iter().map(|error| error.to_string()).collect::<Vec<_>>().join("\n"))]
+pub struct Errors {
+    errors: Vec<MacroError>,
+}
+
+impl Errors {
+    pub fn new() -> Errors {
+        Errors::default()
+    }
+
+    pub fn push(&mut self, error: impl Into<MacroError>) {
+        match error.into() {
+            MacroError::Multiple(errors) => self.errors.extend(errors.errors),
+            error => self.errors.push(error),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MacroError> {
+        self.errors.iter()
+    }
+
+    /// Records the error of a failed result, and returns the value of a
+    /// successful one.
+    pub fn handle<T>(&mut self, result: MacroResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn finish(self) -> MacroResult<()> {
+        self.finish_with(())
+    }
+
+    /// Returns `value` if no errors were collected. A single error is returned
+    /// as is, rather than as [`MacroError::Multiple`].
+    #[allow(clippy::result_large_err)]
+    pub fn finish_with<T>(mut self, value: T) -> MacroResult<T> {
+        match self.errors.len() {
+            0 => Ok(value),
+            1 => Err(self.errors.remove(0)),
+            _ => Err(MacroError::Multiple(self)),
+        }
+    }
+
+    pub fn to_compile_error(&self) -> Tokens {
+        self.errors
+            .iter()
+            .map(|error| error.to_compile_error())
+            .collect()
+    }
+}
+
+impl<E> Extend<E> for Errors
+where
+    E: Into<MacroError>,
+{
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for error in iter {
+            self.push(error);
+        }
+    }
+}
+
+impl<E> FromIterator<E> for Errors
+where
+    E: Into<MacroError>,
+{
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Errors {
+        let mut errors = Errors::new();
+        errors.extend(iter);
+        errors
+    }
+}
+
+impl IntoIterator for Errors {
+    type Item = MacroError;
+    type IntoIter = std::vec::IntoIter<MacroError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+pub trait CollectErrors<T>: Iterator<Item = MacroResult<T>> + Sized {
+    /// Collects the successful values, or every error if any of the results
+    /// failed.
+    fn collect_errors<C>(self) -> Result<C, Errors>
+    where
+        C: FromIterator<T>,
+    {
+        let mut errors = Errors::new();
+        let values: Vec<T> = self.filter_map(|result| errors.handle(result)).collect();
+
+        if errors.is_empty() {
+            Ok(values.into_iter().collect())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<I, T> CollectErrors<T> for I where I: Iterator<Item = MacroResult<T>> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(message: &str) -> MacroError {
+        MacroError::compile_error(Span::call_site(), message)
+    }
+
+    #[test]
+    fn finishes_with_a_single_error_as_is() {
+        let mut errors = Errors::new();
+        errors.push(error("only"));
+
+        assert!(matches!(errors.finish(), Err(MacroError::Custom(_))));
+    }
+
+    #[test]
+    fn flattens_nested_collections() {
+        let mut inner = Errors::new();
+        inner.extend(vec![error("a"), error("b")]);
+
+        let mut errors = Errors::new();
+        errors.push(inner.finish().unwrap_err());
+        errors.push(error("c"));
+
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn collects_every_failed_result() {
+        let results = vec![Ok(1), Err(error("a")), Ok(2), Err(error("b"))];
+        let errors = results.into_iter().collect_errors::<Vec<u8>>().unwrap_err();
+
+        assert_eq!(errors.len(), 2);
+    }
+}
@@ -1,11 +1,17 @@
 pub mod assertions;
+pub mod collect;
+pub mod suggest;
+pub mod warning;
+
+pub use self::collect::{CollectErrors, Errors};
+pub use self::warning::MacroWarning;
 
 use macroscope_utils::{Span, Tokens};
 
 use crate::{
     coerce::compile_error::CompileError,
     error::assertions::{
-        AssertionFailureExpected, AssertionFailureProperty, DiagnosticError, Property,
+        AssertionFailureExpected, AssertionFailureProperty, DiagnosticError, Property, Suggestion,
     },
     impl_prelude::*,
 };
@@ -78,6 +84,8 @@ pub enum MacroError {
     Diagnostic(#[from] DiagnosticError),
     #[error("{0}")]
     Custom(#[from] CustomCompileError),
+    #[error("{0}")]
+    Multiple(#[from] Errors),
 }
 
 impl MacroError {
@@ -96,12 +104,75 @@ impl MacroError {
         })
     }
 
+    /// Replaces the message of every diagnostic in this error, keeping the
+    /// original message as a note. Other errors are left as they are.
+    pub fn message(self, message: &str) -> MacroError {
+        match self {
+            MacroError::Diagnostic(diagnostic) => diagnostic.message(message).into(),
+            MacroError::Multiple(errors) => errors
+                .into_iter()
+                .map(|error| error.message(message))
+                .collect::<Errors>()
+                .into(),
+            error => error,
+        }
+    }
+
+    /// The fixes suggested by the diagnostics in this error.
+    pub fn suggestions(&self) -> Vec<&Suggestion> {
+        match self {
+            MacroError::Diagnostic(diagnostic) => diagnostic.suggestions().iter().collect(),
+            MacroError::Multiple(errors) => errors.iter().flat_map(|e| e.suggestions()).collect(),
+            MacroError::SynError(_) | MacroError::MissingCrate(_) | MacroError::Custom(_) => {
+                vec![]
+            }
+        }
+    }
+
+    /// Renders this error as `compile_error!` invocations. Unlike
+    /// `to_compile_error`, this never goes through the nightly diagnostic
+    /// API, so it has no side effects and works outside of a proc macro.
+    /// `ToTokens` impls use it, since they can run any number of times.
+    pub fn to_error_tokens(&self) -> Tokens {
+        match self {
+            MacroError::Diagnostic(diagnostic) => {
+                diagnostic.clone().into_diagnostic().to_token_stream()
+            }
+            MacroError::Multiple(errors) => errors.iter().map(Self::to_error_tokens).collect(),
+            error => error.to_compile_error(),
+        }
+    }
+
     pub fn to_compile_error(&self) -> Tokens {
         match self {
             MacroError::SynError(error) => error.to_compile_error(),
             MacroError::Custom(custom) => custom.to_compile_error(),
             MacroError::MissingCrate(missing) => missing.to_compile_error(),
             MacroError::Diagnostic(assertion) => assertion.to_compile_error(),
+            MacroError::Multiple(errors) => errors.to_compile_error(),
+        }
+    }
+}
+
+/// For `Parse` impls, which can only fail with a `syn::Error`. Diagnostics
+/// are flattened as described on `From<DiagnosticError> for syn::Error`.
+impl From<MacroError> for syn::Error {
+    fn from(error: MacroError) -> syn::Error {
+        match error {
+            MacroError::SynError(error) => error,
+            MacroError::Diagnostic(diagnostic) => diagnostic.into(),
+            MacroError::Custom(custom) => syn::Error::new(custom.span, custom.reason),
+            MacroError::MissingCrate(missing) => {
+                syn::Error::new(Span::call_site(), missing.to_string())
+            }
+            MacroError::Multiple(errors) => errors
+                .into_iter()
+                .map(syn::Error::from)
+                .reduce(|mut combined, error| {
+                    combined.combine(error);
+                    combined
+                })
+                .unwrap_or_else(|| syn::Error::new(Span::call_site(), "unknown error")),
         }
     }
 }
@@ -0,0 +1,127 @@
+//! "Did you mean" suggestions for misspelled identifiers.
+
+use crate::{error::assertions::DiagnosticError, impl_prelude::*};
+
+/// The optimal string alignment distance between `a` and `b`: the Levenshtein
+/// distance, with swapping two adjacent characters counted as a single edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut before_previous: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 0..a.len() {
+        let mut current = vec![i + 1];
+
+        for j in 0..b.len() {
+            let substitution = previous[j] + if a[i] == b[j] { 0 } else { 1 };
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            let mut distance = substitution.min(insertion).min(deletion);
+
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+
+            current.push(distance);
+        }
+
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+
+    previous[b.len()]
+}
+
+/// The candidate closest to `name`, if any is close enough to be a likely
+/// typo. Like rustc, a candidate can be at most a third of `name`'s length
+/// away (and at least one edit).
+pub fn closest<S>(name: &str, candidates: impl IntoIterator<Item = S>) -> Option<S>
+where
+    S: AsRef<str>,
+{
+    let max = std::cmp::max(name.chars().count(), 3) / 3;
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate.as_ref()), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Adds a "did you mean" suggestion to `error` if `node` is a likely typo of
+/// one of the candidates.
+pub fn did_you_mean<S>(
+    error: DiagnosticError,
+    node: &Identifier,
+    candidates: impl IntoIterator<Item = S>,
+) -> DiagnosticError
+where
+    S: AsRef<str>,
+{
+    let candidate = closest(&node.to_string(), candidates)
+        .and_then(|candidate| syn::parse_str::<syn::Ident>(candidate.as_ref()).ok());
+
+    match candidate {
+        Some(candidate) => {
+            let message = format!("did you mean `{}`?", candidate);
+            error.suggest(Identifier::from(node.as_syn().clone()), candidate, message)
+        }
+        None => error,
+    }
+}
+
+/// An error for an identifier that isn't one of the candidates, such as an
+/// unknown keyword argument, suggesting the closest candidate.
+pub fn unknown<S>(
+    kind: &str,
+    node: &Identifier,
+    candidates: impl IntoIterator<Item = S>,
+) -> DiagnosticError
+where
+    S: AsRef<str>,
+{
+    let error = DiagnosticError::new(node, format!("Unknown {} `{}`", kind, node));
+    did_you_mean(error, node, candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_insertions_deletions_and_substitutions() {
+        assert_eq!(edit_distance("label", "label"), 0);
+        assert_eq!(edit_distance("", "name"), 4);
+        assert_eq!(edit_distance("nam", "name"), 1);
+        assert_eq!(edit_distance("names", "name"), 1);
+        assert_eq!(edit_distance("nane", "name"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn counts_adjacent_swaps_as_one_edit() {
+        assert_eq!(edit_distance("lable", "label"), 1);
+        assert_eq!(edit_distance("nmae", "name"), 1);
+        assert_eq!(edit_distance("ab", "ba"), 1);
+        assert_eq!(edit_distance("abc", "ca"), 3);
+    }
+
+    #[test]
+    fn suggests_the_closest_likely_typo() {
+        let candidates = ["name", "label", "level"];
+
+        assert_eq!(closest("lable", candidates), Some("label"));
+        assert_eq!(closest("nmae", candidates), Some("name"));
+        assert_eq!(closest("labl", candidates), Some("label"));
+        assert_eq!(closest("lvel", candidates), Some("level"));
+    }
+
+    #[test]
+    fn ignores_distant_candidates() {
+        assert_eq!(closest("id", ["name", "label"]), None);
+        assert_eq!(closest("lbl", ["label"]), None);
+        assert_eq!(closest("anything", Vec::<&str>::new()), None);
+    }
+}
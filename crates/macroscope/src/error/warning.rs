@@ -0,0 +1,93 @@
+use crate::impl_prelude::*;
+
+#[cfg(feature = "nightly")]
+extern crate proc_macro;
+
+/// A warning reported at a span in the macro's input, e.g. a deprecation
+/// notice for old attribute syntax.
+///
+/// Stable Rust has no API for emitting warnings from proc macros, so by
+/// default the warning's tokens are an item that uses a `#[deprecated]`
+/// constant at the span, which makes rustc report "use of deprecated
+/// constant" with the message. They must be placed where an item is allowed.
+///
+/// With the `nightly` feature, the warning is emitted through
+/// `proc_macro::Diagnostic` when converted to tokens, and the tokens are
+/// empty.
+#[derive(Debug, Clone)]
+pub struct MacroWarning {
+    span: Span,
+    message: String,
+    notes: Vec<String>,
+}
+
+impl MacroWarning {
+    pub fn new(node: &impl AstNode, message: impl Into<String>) -> MacroWarning {
+        MacroWarning::spanned(node.span(), message)
+    }
+
+    pub fn spanned(span: Span, message: impl Into<String>) -> MacroWarning {
+        MacroWarning {
+            span,
+            message: message.into(),
+            notes: vec![],
+        }
+    }
+
+    pub fn note(mut self, message: impl Into<String>) -> Self {
+        self.notes.push(message.into());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[cfg(not(feature = "nightly"))]
+    pub fn into_tokens(self) -> Tokens {
+        let mut note = self.message;
+
+        for message in self.notes {
+            note.push_str("\n= note: ");
+            note.push_str(&message);
+        }
+
+        let warning = syn::Ident::new("warning", self.span);
+
+        tokens! {
+            const _: () = {
+                #[deprecated(note = #note)]
+                #[allow(non_upper_case_globals)]
+                const warning: () = ();
+
+                #[allow(dead_code)]
+                fn macroscope_warning() {
+                    let _ = #warning;
+                }
+            };
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    pub fn into_tokens(self) -> Tokens {
+        let mut diagnostic = proc_macro::Diagnostic::spanned(
+            self.span.unwrap(),
+            proc_macro::Level::Warning,
+            self.message,
+        );
+
+        for message in self.notes {
+            diagnostic = diagnostic.note(message);
+        }
+
+        diagnostic.emit();
+
+        Tokens::new()
+    }
+}
+
+impl ToTokens for MacroWarning {
+    fn to_tokens(&self, tokens: &mut Tokens) {
+        tokens.extend(self.clone().into_tokens())
+    }
+}
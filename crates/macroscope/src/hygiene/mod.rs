@@ -2,6 +2,7 @@ use crate::error::MacroResult;
 
 use crate::impl_prelude::*;
 
+#[allow(clippy::result_large_err)]
 pub fn dollar_crate(crate_name: impl AsRef<str>) -> MacroResult<Tokens> {
     use crate::tools::proc_macro_crate::{crate_name as find_crate, FoundCrate};
 
@@ -1,9 +1,9 @@
 pub(crate) use crate::ast::newtype::AstNewtype;
 pub(crate) use crate::ast::*;
 pub(crate) use crate::prelude::*;
-pub(crate) use crate::tools::derive_syn_parse::Parse as SynParse;
 pub(crate) use crate::tools::proc_macro_error::Diagnostic;
 pub(crate) use crate::tools::quote::ToTokens;
 pub(crate) use crate::tools::syn::Token as SynToken;
 pub(crate) use crate::tools::*;
+pub(crate) use crate::Syntax;
 pub(crate) use derive_new::new;
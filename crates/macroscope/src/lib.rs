@@ -1,17 +1,21 @@
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
+
 pub mod assertions;
 pub mod ast;
 pub mod coerce;
 pub mod derive_parse;
+pub mod entry;
 pub mod error;
 pub mod gensym;
 pub mod hygiene;
 pub(crate) mod impl_prelude;
 pub mod parse;
 pub mod prelude;
+pub mod testing;
 pub mod wrap;
 
-pub use self::{gensym::unique_ident, hygiene::dollar_crate};
-pub use macroscope_macro::build_using;
+pub use self::{entry::entry_point, gensym::unique_ident, hygiene::dollar_crate};
+pub use macroscope_macro::{attribute_macro, build_using, derive_macro, function_macro, Syntax};
 pub use macroscope_utils::tools::quote::{quote, quote_spanned};
 pub use macroscope_utils::{find_crate, quote_crate, tools, Tokens};
 
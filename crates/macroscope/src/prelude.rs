@@ -5,8 +5,12 @@ pub use crate::coerce::{
     compile_error::{CompileError, ToCompileError},
     tokens::ProcMacroTokens,
 };
-pub use crate::derive_parse::{delimited::*, separated::Separated};
-pub use crate::error::{MacroError, MacroResult};
+pub use crate::derive_parse::{
+    delimited::*,
+    meta::{FromMeta, MetaArgs},
+    separated::Separated,
+};
+pub use crate::error::{CollectErrors, Errors, MacroError, MacroResult, MacroWarning};
 pub use crate::hygiene::dollar_crate;
 pub use crate::{macro_error, tokens};
 pub use macroscope_utils::tools::quote::{self, format_ident};
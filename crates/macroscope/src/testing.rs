@@ -0,0 +1,311 @@
+//! Round-trip checks for grammars: parse some tokens, print them back out and
+//! compare the two token streams, ignoring spacing.
+//!
+//! ```ignore
+//! use macroscope::testing::assert_round_trip;
+//!
+//! assert_round_trip::<Parenthesized<Separated<syn::Ident, Token![,]>>>(quote!((a, b, c)));
+//! ```
+
+use std::fmt::{self, Display};
+
+use crate::tools::proc_macro2::{Delimiter, TokenStream, TokenTree};
+use crate::tools::quote::ToTokens;
+use crate::tools::syn::{self, parse::Parse};
+
+/// A failed round trip.
+#[derive(Debug, thiserror::Error)]
+pub enum RoundTripError {
+    #[error("could not parse `{input}`: {error}")]
+    Parse { input: String, error: syn::Error },
+    #[error("printed tokens differ from the parsed tokens:\n{diff}")]
+    Mismatch { diff: TokenDiff },
+}
+
+/// Parses `input` as `T` and checks that printing the result gives back the
+/// same tokens.
+pub fn round_trip<T>(input: TokenStream) -> Result<T, RoundTripError>
+where
+    T: Parse + ToTokens,
+{
+    let parsed: T = syn::parse2(input.clone()).map_err(|error| RoundTripError::Parse {
+        input: input.to_string(),
+        error,
+    })?;
+
+    let diff = TokenDiff::new(&input, &parsed.to_token_stream());
+
+    if diff.is_same() {
+        Ok(parsed)
+    } else {
+        Err(RoundTripError::Mismatch { diff })
+    }
+}
+
+/// Like [`round_trip`], panicking with the token diff on failure.
+#[track_caller]
+pub fn assert_round_trip<T>(input: TokenStream) -> T
+where
+    T: Parse + ToTokens,
+{
+    match round_trip(input) {
+        Ok(parsed) => parsed,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Whether `left` and `right` are the same tokens, ignoring spacing and
+/// invisible groups.
+pub fn tokens_eq(left: &TokenStream, right: &TokenStream) -> bool {
+    flatten(left) == flatten(right)
+}
+
+#[derive(Debug, Clone)]
+enum DiffLine {
+    Same(String),
+    Expected(String),
+    Actual(String),
+}
+
+/// A token-by-token diff between the expected and actual tokens. Lines
+/// starting with `-` are only in the expected tokens, `+` only in the actual.
+#[derive(Debug, Clone)]
+pub struct TokenDiff {
+    lines: Vec<DiffLine>,
+}
+
+impl TokenDiff {
+    pub fn new(expected: &TokenStream, actual: &TokenStream) -> TokenDiff {
+        let expected = flatten(expected);
+        let actual = flatten(actual);
+
+        // Longest common subsequence, walked from the front.
+        let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+        for i in (0..expected.len()).rev() {
+            for j in (0..actual.len()).rev() {
+                lcs[i][j] = if expected[i] == actual[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut lines = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < expected.len() && j < actual.len() {
+            if expected[i] == actual[j] {
+                lines.push(DiffLine::Same(expected[i].clone()));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                lines.push(DiffLine::Expected(expected[i].clone()));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Actual(actual[j].clone()));
+                j += 1;
+            }
+        }
+
+        lines.extend(expected[i..].iter().cloned().map(DiffLine::Expected));
+        lines.extend(actual[j..].iter().cloned().map(DiffLine::Actual));
+
+        TokenDiff { lines }
+    }
+
+    /// Whether the two token streams are the same.
+    pub fn is_same(&self) -> bool {
+        self.lines
+            .iter()
+            .all(|line| matches!(line, DiffLine::Same(_)))
+    }
+}
+
+impl Display for TokenDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                DiffLine::Same(token) => writeln!(f, "  {}", token)?,
+                DiffLine::Expected(token) => writeln!(f, "- {}", token)?,
+                DiffLine::Actual(token) => writeln!(f, "+ {}", token)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One string per token, with groups opened and closed by their delimiters.
+fn flatten(tokens: &TokenStream) -> Vec<String> {
+    let mut flat = vec![];
+    flatten_into(tokens, &mut flat);
+    flat
+}
+
+fn flatten_into(tokens: &TokenStream, flat: &mut Vec<String>) {
+    for token in tokens.clone() {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+
+                if !open.is_empty() {
+                    flat.push(open.to_string());
+                }
+                flatten_into(&group.stream(), flat);
+                if !close.is_empty() {
+                    flat.push(close.to_string());
+                }
+            }
+            TokenTree::Punct(punct) => flat.push(punct.as_char().to_string()),
+            TokenTree::Ident(ident) => flat.push(ident.to_string()),
+            TokenTree::Literal(literal) => flat.push(literal.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive_parse::{
+        delimited::{Angled, Braced, Bracketed, Parenthesized, Piped},
+        optional::{Optional, OptionalEnd},
+        pair::{Pair, Prefixed, Suffixed},
+        separated::{Separated, SeparatedInline},
+        tail::ListWithTail,
+    };
+    use crate::tools::proc_macro2::Group;
+    use crate::tools::quote::quote;
+    use crate::tools::syn::{Ident, LitInt, Token};
+
+    type List = Separated<Ident, Token![,]>;
+
+    #[test]
+    fn delimited_round_trips_every_delimiter() {
+        assert_round_trip::<Parenthesized<List>>(quote!((a, b)));
+        assert_round_trip::<Bracketed<List>>(quote!([a, b]));
+        assert_round_trip::<Braced<List>>(quote!({ a, b }));
+        assert_round_trip::<Parenthesized<List>>(quote!(()));
+
+        // Pipes and angles aren't token groups, so their content has to stop
+        // by itself.
+        type Inline = SeparatedInline<Ident, Token![,]>;
+        assert_round_trip::<Piped<Inline>>(quote!(|a, b|));
+        assert_round_trip::<Angled<Inline>>(quote!(<a, b>));
+    }
+
+    #[test]
+    fn delimited_rejects_the_wrong_delimiter() {
+        assert!(round_trip::<Parenthesized<List>>(quote!([a, b])).is_err());
+        assert!(round_trip::<Angled<SeparatedInline<Ident, Token![,]>>>(quote!(<a, b)).is_err());
+    }
+
+    #[test]
+    fn separated_round_trips_with_and_without_a_trailing_separator() {
+        assert_eq!(assert_round_trip::<List>(quote!()).len(), 0);
+        assert_eq!(assert_round_trip::<List>(quote!(a)).len(), 1);
+        assert_eq!(assert_round_trip::<List>(quote!(a, b, c)).len(), 3);
+        assert_eq!(assert_round_trip::<List>(quote!(a, b, c,)).len(), 3);
+        assert!(round_trip::<List>(quote!(a b)).is_err());
+    }
+
+    #[test]
+    fn list_with_tail_round_trips() {
+        type Items = ListWithTail<Suffixed<Ident, Token![;]>, LitInt>;
+
+        let parsed = assert_round_trip::<Items>(quote!(a; b; 1));
+        assert_eq!(parsed.head.len(), 2);
+
+        let parsed = assert_round_trip::<Items>(quote!(1));
+        assert!(parsed.head.is_empty());
+
+        assert!(round_trip::<Items>(quote!(a; b;)).is_err());
+    }
+
+    #[test]
+    fn list_with_tail_leaves_the_last_item_for_the_tail() {
+        let parsed = assert_round_trip::<ListWithTail<Ident, Ident>>(quote!(a b c));
+
+        assert_eq!(parsed.head.len(), 2);
+        assert_eq!(parsed.tail, "c");
+    }
+
+    #[test]
+    fn optional_round_trips_present_and_missing() {
+        type Item = Pair<Optional<Token![pub]>, Ident>;
+
+        let parsed = assert_round_trip::<Item>(quote!(pub a));
+        assert!(matches!(parsed.left, Optional::Present(_)));
+
+        let parsed = assert_round_trip::<Item>(quote!(a));
+        assert!(matches!(parsed.left, Optional::Missing));
+
+        let parsed = assert_round_trip::<Pair<Ident, OptionalEnd<LitInt>>>(quote!(a));
+        assert!(matches!(parsed.right, OptionalEnd::Missing));
+        assert_round_trip::<Pair<Ident, OptionalEnd<LitInt>>>(quote!(a 1));
+    }
+
+    #[test]
+    fn prefixed_and_suffixed_round_trip() {
+        let prefixed = assert_round_trip::<Prefixed<Token![pub], Ident>>(quote!(pub a));
+        assert_eq!(prefixed.item, "a");
+
+        let suffixed = assert_round_trip::<Suffixed<Ident, Token![;]>>(quote!(a;));
+        assert_eq!(suffixed.item, "a");
+    }
+
+    #[test]
+    fn suffixed_requires_its_suffix() {
+        assert!(round_trip::<Suffixed<Ident, Token![;]>>(quote!(a)).is_err());
+        assert!(round_trip::<Suffixed<Ident, Token![;]>>(quote!(a,)).is_err());
+        assert!(round_trip::<Suffixed<Ident, Token![;]>>(quote!(a;;)).is_err());
+    }
+
+    #[test]
+    fn pair_round_trips_nested_pairs() {
+        type Nested = Pair<Pair<Ident, Token![:]>, Pair<Ident, Optional<Token![?]>>>;
+
+        let parsed = assert_round_trip::<Nested>(quote!(a: b?));
+        assert!(matches!(parsed.right.right, Optional::Present(_)));
+
+        let parsed = assert_round_trip::<Nested>(quote!(a: b));
+        assert!(matches!(parsed.right.right, Optional::Missing));
+
+        assert!(round_trip::<Nested>(quote!(a b)).is_err());
+    }
+
+    #[test]
+    fn reports_a_diff_when_printing_changes_the_tokens() {
+        struct Dropped;
+
+        impl Parse for Dropped {
+            fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+                input.parse::<Ident>()?;
+                Ok(Dropped)
+            }
+        }
+
+        impl ToTokens for Dropped {
+            fn to_tokens(&self, _: &mut TokenStream) {}
+        }
+
+        let error = round_trip::<Dropped>(quote!(a)).err().unwrap();
+        assert!(matches!(error, RoundTripError::Mismatch { .. }));
+        assert!(error.to_string().contains("- a"));
+    }
+
+    #[test]
+    fn compares_tokens_ignoring_spacing_and_invisible_groups() {
+        let grouped =
+            TokenStream::from(TokenTree::Group(Group::new(Delimiter::None, quote!(a + b))));
+
+        assert!(tokens_eq(&quote!(a + b), &quote!(a + b)));
+        assert!(tokens_eq(&grouped, &quote!(a + b)));
+        assert!(!tokens_eq(&quote!((a + b)), &quote!(a + b)));
+    }
+}
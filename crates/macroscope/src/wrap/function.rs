@@ -1,34 +1,85 @@
-use crate::{assertions::traits::Assertion, impl_prelude::*};
+use crate::{
+    assertions::traits::{assert_all, Assertion},
+    impl_prelude::*,
+};
 use macroscope_utils::{tools::quote::format_ident, Tokens};
+use syn::punctuated::Punctuated;
 
 use crate::tokens;
 
 use crate::{error::MacroResult, parse_tokens};
 
+/// How the wrapper of an `async fn` sees the inner function. In both modes
+/// the outer function stays `async`, so borrowed parameters keep working
+/// without spelling out the lifetimes captured by the future.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum AsyncMode {
+    /// The wrapper receives the inner function's future and returns a future,
+    /// which the outer function awaits: `wrapper(inner()).await`. Use this
+    /// for wrappers like timeouts and spans that need to poll the future.
+    Future,
+    /// The wrapper receives the awaited output: `wrapper(inner().await)`.
+    /// This lets a wrapper written for synchronous functions also wrap
+    /// `async fn`s.
+    #[default]
+    Output,
+}
+
+type AfterHook = Box<dyn Fn(&syn::Ident) -> Tokens>;
+type AroundHook = Box<dyn Fn(Tokens) -> Tokens>;
+type ReturnTypeMapper = Box<dyn Fn(ReturnType) -> ReturnType>;
+
 pub struct WrapFn {
     input: Function,
-    wrapper: Tokens,
     assertions: Vec<Assertion<Function>>,
+    before: Vec<Tokens>,
+    after: Vec<AfterHook>,
+    around: Vec<AroundHook>,
+    return_type: Option<ReturnTypeMapper>,
+    in_impl: bool,
+    async_mode: AsyncMode,
 }
 
 impl ToTokens for WrapFn {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         match self.emit() {
             Ok(output) => tokens.extend(output),
-            Err(err) => tokens.extend(err.to_compile_error()),
+            Err(err) => tokens.extend(err.to_error_tokens()),
         }
     }
 }
 
 impl WrapFn {
+    #[allow(clippy::result_large_err)]
     pub fn parse(input: Tokens, wrapper: Tokens) -> MacroResult<WrapFn> {
         let input = parse_tokens!(input)?;
 
-        Ok(WrapFn {
+        Ok(WrapFn::new(input).wrapper(wrapper))
+    }
+
+    pub fn new(input: Function) -> WrapFn {
+        WrapFn {
             input,
-            wrapper,
             assertions: vec![],
-        })
+            before: vec![],
+            after: vec![],
+            around: vec![],
+            return_type: None,
+            in_impl: false,
+            async_mode: AsyncMode::default(),
+        }
+    }
+
+    /// Marks the function as an associated item of an impl block, so the
+    /// wrapped body can keep referring to `Self` even without a receiver.
+    pub fn in_impl(mut self) -> Self {
+        self.in_impl = true;
+        self
+    }
+
+    pub fn async_mode(mut self, mode: AsyncMode) -> Self {
+        self.async_mode = mode;
+        self
     }
 
     pub fn assert(mut self, assertion: impl AstAssertion<Function>) -> Self {
@@ -36,40 +87,663 @@ impl WrapFn {
         self
     }
 
+    /// The identifiers the outer function binds its parameters to, in order
+    /// and excluding `self`. Hooks can use them to refer to the arguments:
+    /// parameters with destructuring patterns are bound to a generated
+    /// identifier that only these tokens can name.
+    pub fn params(&self) -> Vec<syn::Ident> {
+        Forwarded::new(&self.input.signature()).args
+    }
+
+    /// Calls `wrapper` with the inner call: `wrapper(inner(args))`.
+    pub fn wrapper(self, wrapper: Tokens) -> Self {
+        self.around(move |call| tokens!(#wrapper(#call)))
+    }
+
+    /// Runs `statements` before the inner function is called.
+    pub fn before(mut self, statements: Tokens) -> Self {
+        self.before.push(statements);
+        self
+    }
+
+    /// Runs the statements returned by `hook` after the inner function
+    /// returns. `hook` receives the identifier the result is bound to.
+    pub fn after(mut self, hook: impl Fn(&syn::Ident) -> Tokens + 'static) -> Self {
+        self.after.push(Box::new(hook));
+        self
+    }
+
+    /// Replaces the inner call with the expression returned by `hook`, which
+    /// receives the call expression. Each hook wraps the ones registered
+    /// before it.
+    pub fn around(mut self, hook: impl Fn(Tokens) -> Tokens + 'static) -> Self {
+        self.around.push(Box::new(hook));
+        self
+    }
+
+    /// Declares that the wrapper changes the function's return type. The outer
+    /// function returns the type produced by `mapper`, while the inner
+    /// function keeps the original one. [`ReturnType::map`] handles functions
+    /// without an explicit return type.
+    pub fn map_return_type(mut self, mapper: impl Fn(ReturnType) -> ReturnType + 'static) -> Self {
+        self.return_type = Some(Box::new(mapper));
+        self
+    }
+
+    #[allow(clippy::result_large_err)]
     pub fn emit(&self) -> MacroResult<Tokens> {
         let Self {
             input: func,
-            wrapper,
             assertions,
+            before,
+            after,
+            around,
+            return_type,
+            in_impl,
+            async_mode,
         } = self;
 
-        for assertion in assertions {
-            assertion.assert(&func)?;
-        }
+        assert_all(assertions, func)?;
+
+        let signature = func.signature();
+        let forwarded = Forwarded::new(&signature);
+        let inner_name = format_ident!("inner_{}", func.name());
+
+        let inner = if *in_impl || signature.parameters().has_self() {
+            Inner::closure(func, &forwarded, inner_name)
+        } else {
+            Inner::nested_fn(func, &forwarded, inner_name)
+        };
+
+        let Inner { definition, call } = inner;
 
         let attrs = func.attrs();
         let visibility = func.visibility();
-        let fn_name = func.name();
-        let fn_token = func.fn_token();
-        let qualifiers = func.signature().qualifiers();
-        let generics = func.signature().generic_params();
-        let where_clause = func.signature().where_clause();
-        let return_type = func.signature().return_type();
-        let body = func.body();
+        let outer = match return_type {
+            Some(mapper) => {
+                let mapped = mapper(signature.return_type());
+                signature.with(forwarded.parameters).with(mapped)
+            }
+            None => signature.with(forwarded.parameters),
+        };
+        let is_async = outer.asyncness().is_some();
+
+        let call = match async_mode {
+            AsyncMode::Output if is_async => tokens!(#call.await),
+            _ => call,
+        };
+
+        let call = around.iter().fold(call, |call, hook| hook(call));
 
-        let inner_name = format_ident!("inner_{}", fn_name);
+        let call = match async_mode {
+            AsyncMode::Future if is_async => tokens!(#call.await),
+            _ => call,
+        };
 
-        let _outer = func.signature().without(Async::default());
+        let output = if after.is_empty() {
+            call
+        } else {
+            let result = format_ident!("result", span = Span::mixed_site());
+            let after = after.iter().map(|hook| hook(&result));
+
+            tokens! {
+                let #result = #call;
+                #(#after)*
+                #result
+            }
+        };
 
         Ok(tokens! {
-            #visibility #fn_token #fn_name #generics() #return_type #where_clause {
-                #(#attrs)*
-                #qualifiers #fn_token #inner_name #generics() #return_type {
+            #(#attrs)*
+            #visibility #outer {
+                #(#before)*
+
+                #definition
+
+                #output
+            }
+        })
+    }
+}
+
+/// The parameters of the outer function. Every parameter is bound to a plain
+/// identifier, generating one for destructuring patterns, so that it can be
+/// forwarded to the inner function.
+struct Forwarded {
+    parameters: FnParameters,
+    args: Vec<syn::Ident>,
+    rebind: Vec<(syn::Pat, syn::Ident)>,
+}
+
+impl Forwarded {
+    fn new(signature: &Signature) -> Forwarded {
+        let mut parameters = Punctuated::<syn::FnArg, SynToken![,]>::new();
+        let mut args = vec![];
+        let mut rebind = vec![];
+
+        if let Some(receiver) = signature.parameters().self_param() {
+            parameters.push(receiver.into_syn());
+        }
+
+        for (index, param) in signature.parameters().params().into_pairs().enumerate() {
+            let param = param.into_item();
+            let pattern = param.pattern();
+            let ty = param.ty();
+
+            let ident = match param.binding() {
+                Some(ident) => ident.into_syn(),
+                None => format_ident!("arg{}", index, span = Span::mixed_site()),
+            };
+
+            if !matches!(&pattern, syn::Pat::Ident(binding) if binding.mutability.is_none()) {
+                rebind.push((pattern, ident.clone()));
+            }
+
+            parameters.push(syn::parse_quote!(#ident: #ty));
+            args.push(ident);
+        }
+
+        Forwarded {
+            parameters: FnParameters::from(Separated::from(parameters)),
+            args,
+            rebind,
+        }
+    }
+}
+
+/// The inner function that the outer function delegates to: its definition
+/// and the expression that calls it. The original attributes stay on the
+/// outer function either way.
+struct Inner {
+    definition: Tokens,
+    call: Tokens,
+}
+
+impl Inner {
+    /// Moves the original function into a nested `fn` item, which keeps the
+    /// original parameter patterns.
+    fn nested_fn(func: &Function, forwarded: &Forwarded, inner_name: syn::Ident) -> Inner {
+        let signature = func.signature();
+
+        let fn_token = func.fn_token();
+        let qualifiers = signature.qualifiers();
+        let generics = signature.generic_params();
+        let params = signature.parameters();
+        let return_type = signature.return_type();
+        let where_clause = signature.where_clause();
+        let turbofish = signature.turbofish();
+        let body = func.body();
+        let args = &forwarded.args;
+
+        Inner {
+            definition: tokens! {
+                #qualifiers #fn_token #inner_name #generics(#params) #return_type #where_clause
                     #body
+            },
+            call: tokens!(#inner_name #turbofish(#(#args),*)),
+        }
+    }
+
+    /// Moves the original body into a closure. A nested `fn` can't refer to
+    /// `self` (or `Self`), so methods and other associated functions capture
+    /// their receiver and parameters instead of forwarding them, and restore
+    /// the original parameter patterns inside the closure.
+    ///
+    /// There are no async closures, so the closure of an `async fn` returns
+    /// an `async move` block instead.
+    fn closure(func: &Function, forwarded: &Forwarded, inner_name: syn::Ident) -> Inner {
+        let signature = func.signature();
+
+        let output = match signature.return_type().into_syn() {
+            syn::ReturnType::Type(_, ty) if mentions_impl_trait(ty.to_token_stream()) => None,
+            syn::ReturnType::Type(_, ty) => Some(ty),
+            syn::ReturnType::Default => None,
+        };
+
+        let patterns = forwarded.rebind.iter().map(|(pattern, _)| pattern);
+        let idents = forwarded.rebind.iter().map(|(_, ident)| ident);
+        let original = func.body();
+        let body = if forwarded.rebind.is_empty() {
+            original.to_token_stream()
+        } else {
+            tokens!({
+                #(let #patterns = #idents;)*
+                #original
+            })
+        };
+
+        let closure = match (signature.asyncness(), output) {
+            (None, Some(ty)) => tokens!(move || -> #ty #body),
+            (None, None) => tokens!(move || #body),
+            // Annotating the block's result tells `?` in the body what to
+            // convert errors into.
+            (Some(_), Some(ty)) => tokens!(move || async move {
+                let output: #ty = #body;
+                output
+            }),
+            (Some(_), None) => tokens!(move || async move #body),
+        };
+
+        Inner {
+            definition: tokens! {
+                #[allow(unused_mut)]
+                let mut #inner_name = #closure;
+            },
+            call: tokens!(#inner_name()),
+        }
+    }
+}
+
+/// Closures can't declare `impl Trait` return types, so those are left to
+/// inference.
+fn mentions_impl_trait(tokens: Tokens) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => ident == "impl",
+        proc_macro2::TokenTree::Group(group) => mentions_impl_trait(group.stream()),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TokenDiff;
+
+    fn wrap(input: Tokens) -> WrapFn {
+        WrapFn::parse(input, tokens!(wrap)).unwrap()
+    }
+
+    fn assert_emits(wrap_fn: WrapFn, expected: Tokens) {
+        let diff = TokenDiff::new(&expected, &wrap_fn.emit().unwrap());
+        assert!(diff.is_same(), "{}", diff);
+    }
+
+    #[test]
+    fn forwards_patterns_through_bindings() {
+        assert_emits(
+            wrap(tokens!(
+                pub fn f((a, b): (u8, u8), mut c: u8, _: u8) -> u8 {
+                    a + b + c
+                }
+            )),
+            tokens!(
+                pub fn f(arg0: (u8, u8), c: u8, arg2: u8) -> u8 {
+                    fn inner_f((a, b): (u8, u8), mut c: u8, _: u8) -> u8 {
+                        a + b + c
+                    }
+
+                    wrap(inner_f(arg0, c, arg2))
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn keeps_attributes_on_the_outer_function() {
+        assert_emits(
+            wrap(tokens!(
+                #[inline]
+                #[must_use]
+                pub fn f(x: u8) -> u8 {
+                    x
+                }
+            )),
+            tokens!(
+                #[inline]
+                #[must_use]
+                pub fn f(x: u8) -> u8 {
+                    fn inner_f(x: u8) -> u8 {
+                        x
+                    }
+
+                    wrap(inner_f(x))
+                }
+            ),
+        );
+
+        assert_emits(
+            wrap(tokens!(
+                #[inline]
+                #[must_use]
+                pub fn f(&self) -> u8 {
+                    self.0
+                }
+            )),
+            tokens!(
+                #[inline]
+                #[must_use]
+                pub fn f(&self) -> u8 {
+                    #[allow(unused_mut)]
+                    let mut inner_f = move || -> u8 { self.0 };
+
+                    wrap(inner_f())
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn passes_type_and_const_generics_by_turbofish() {
+        assert_emits(
+            wrap(tokens!(
+                fn g<'a, T: Clone, const N: usize>(x: &'a T) -> [T; N]
+                where
+                    T: Copy,
+                {
+                    [*x; N]
+                }
+            )),
+            tokens!(
+                fn g<'a, T: Clone, const N: usize>(x: &'a T) -> [T; N]
+                where
+                    T: Copy,
+                {
+                    fn inner_g<'a, T: Clone, const N: usize>(x: &'a T) -> [T; N]
+                    where
+                        T: Copy,
+                    {
+                        [*x; N]
+                    }
+
+                    wrap(inner_g::<T, N>(x))
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn captures_receivers_in_a_closure() {
+        assert_emits(
+            wrap(tokens!(
+                fn m(&mut self, (a, b): (u8, u8)) -> u8 {
+                    self.0 + a + b
+                }
+            )),
+            tokens!(
+                fn m(&mut self, arg0: (u8, u8)) -> u8 {
+                    #[allow(unused_mut)]
+                    let mut inner_m = move || -> u8 {
+                        let (a, b) = arg0;
+                        {
+                            self.0 + a + b
+                        }
+                    };
+
+                    wrap(inner_m())
                 }
+            ),
+        );
+    }
+
+    #[test]
+    fn captures_typed_receivers_in_a_closure() {
+        assert_emits(
+            wrap(tokens!(
+                fn m(self: Box<Self>, x: u8) -> u8 {
+                    self.0 + x
+                }
+            )),
+            tokens!(
+                fn m(self: Box<Self>, x: u8) -> u8 {
+                    #[allow(unused_mut)]
+                    let mut inner_m = move || -> u8 { self.0 + x };
+
+                    wrap(inner_m())
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn uses_a_closure_for_associated_functions() {
+        assert_emits(
+            wrap(tokens!(
+                fn new() -> Self {
+                    Self
+                }
+            ))
+            .in_impl(),
+            tokens!(
+                fn new() -> Self {
+                    #[allow(unused_mut)]
+                    let mut inner_new = move || -> Self { Self };
+
+                    wrap(inner_new())
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn awaits_the_inner_call_in_output_mode() {
+        assert_emits(
+            wrap(tokens!(
+                async fn h(x: &str) -> usize {
+                    x.len()
+                }
+            )),
+            tokens!(
+                async fn h(x: &str) -> usize {
+                    async fn inner_h(x: &str) -> usize {
+                        x.len()
+                    }
+
+                    wrap(inner_h(x).await)
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn passes_the_future_to_the_wrapper_in_future_mode() {
+        assert_emits(
+            wrap(tokens!(
+                async fn h(x: &str) -> usize {
+                    x.len()
+                }
+            ))
+            .async_mode(AsyncMode::Future),
+            tokens!(
+                async fn h(x: &str) -> usize {
+                    async fn inner_h(x: &str) -> usize {
+                        x.len()
+                    }
+
+                    wrap(inner_h(x)).await
+                }
+            ),
+        );
+    }
 
-                #wrapper(#inner_name())
+    #[test]
+    fn annotates_the_output_of_async_methods() {
+        assert_emits(
+            wrap(tokens!(
+                async fn h(&self) -> Result<u8, E> {
+                    Ok(self.get()?)
+                }
+            )),
+            tokens!(
+                async fn h(&self) -> Result<u8, E> {
+                    #[allow(unused_mut)]
+                    let mut inner_h = move || async move {
+                        let output: Result<u8, E> = { Ok(self.get()?) };
+                        output
+                    };
+
+                    wrap(inner_h().await)
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn runs_after_hooks_on_the_result() {
+        assert_emits(
+            wrap(tokens!(
+                fn f(x: u8) -> u8 {
+                    x
+                }
+            ))
+            .after(|result| tokens!(log(&#result);)),
+            tokens!(
+                fn f(x: u8) -> u8 {
+                    fn inner_f(x: u8) -> u8 {
+                        x
+                    }
+
+                    let result = wrap(inner_f(x));
+                    log(&result);
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn composes_hooks_in_registration_order() {
+        assert_emits(
+            WrapFn::parse(
+                tokens!(
+                    fn f(x: u8) -> u8 {
+                        x
+                    }
+                ),
+                tokens!(first),
+            )
+            .unwrap()
+            .before(tokens!(setup();))
+            .before(tokens!(check();))
+            .around(|call| tokens!(second(#call)))
+            .after(|result| tokens!(log(&#result);))
+            .after(|result| tokens!(teardown(&#result);)),
+            tokens!(
+                fn f(x: u8) -> u8 {
+                    setup();
+                    check();
+
+                    fn inner_f(x: u8) -> u8 {
+                        x
+                    }
+
+                    let result = second(first(inner_f(x)));
+                    log(&result);
+                    teardown(&result);
+                    result
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn names_destructured_params_after_their_position() {
+        let wrap_fn = wrap(tokens!(
+            fn m(&self, (a, b): (u8, u8), c: u8, _: u8) {}
+        ));
+        let params: Vec<String> = wrap_fn.params().iter().map(|p| p.to_string()).collect();
+
+        assert_eq!(params, ["arg0", "c", "arg2"]);
+
+        let params = wrap_fn.params();
+        assert_emits(
+            wrap_fn.before(tokens!(check(#(#params),*);)),
+            tokens!(
+                fn m(&self, arg0: (u8, u8), c: u8, arg2: u8) {
+                    check(arg0, c, arg2);
+
+                    #[allow(unused_mut)]
+                    let mut inner_m = move || {
+                        let (a, b) = arg0;
+                        let _ = arg2;
+                        {}
+                    };
+
+                    wrap(inner_m())
+                }
+            ),
+        );
+    }
+
+    fn optional(return_type: ReturnType) -> ReturnType {
+        return_type.map(|ty| syn::parse_quote!(Option<#ty>))
+    }
+
+    #[test]
+    fn maps_missing_and_unit_return_types_as_unit() {
+        let expected = tokens!(
+            fn f() -> Option<()> {
+                fn inner_f() {
+                    g()
+                }
+
+                wrap(inner_f())
             }
-        })
+        );
+
+        assert_emits(
+            wrap(tokens!(
+                fn f() {
+                    g()
+                }
+            ))
+            .map_return_type(optional),
+            expected,
+        );
+
+        assert_emits(
+            wrap(tokens!(
+                fn f() -> () {
+                    g()
+                }
+            ))
+            .map_return_type(optional),
+            tokens!(
+                fn f() -> Option<()> {
+                    fn inner_f() -> () {
+                        g()
+                    }
+
+                    wrap(inner_f())
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn maps_impl_trait_return_types() {
+        assert_emits(
+            wrap(tokens!(
+                fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+                    let items = self.0.iter();
+                    items.copied()
+                }
+            ))
+            .map_return_type(optional),
+            tokens!(
+                fn iter(&self) -> Option<impl Iterator<Item = u8> + '_> {
+                    #[allow(unused_mut)]
+                    let mut inner_iter = move || {
+                        let items = self.0.iter();
+                        items.copied()
+                    };
+
+                    wrap(inner_iter())
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn renders_failed_assertions_as_compile_errors() {
+        let wrap_fn = wrap(tokens!(
+            fn f() {}
+        ))
+        .assert(crate::assertions::function::AssertAsync);
+
+        let first = wrap_fn.to_token_stream().to_string();
+        let second = wrap_fn.to_token_stream().to_string();
+
+        assert!(first.contains("compile_error"), "{}", first);
+        assert_eq!(first, second);
     }
 }
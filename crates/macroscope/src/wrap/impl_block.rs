@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+
+use crate::{
+    assertions::traits::{assert_all, Assertion},
+    error::Errors,
+    impl_prelude::*,
+    wrap::function::WrapFn,
+};
+use macroscope_utils::Tokens;
+
+use crate::{error::MacroResult, parse_tokens};
+
+type MethodFilter = Box<dyn Fn(&Function) -> bool>;
+type Configure = Box<dyn Fn(WrapFn) -> WrapFn>;
+
+/// Wraps every method of an impl block the same way, as if each one had been
+/// passed through a [`WrapFn`] set up by [`configure`](WrapImpl::configure).
+/// Filters select which methods are wrapped; the rest are emitted unchanged.
+pub struct WrapImpl {
+    input: Impl,
+    configure: Vec<Configure>,
+    filters: Vec<MethodFilter>,
+    assertions: Vec<Assertion<Function>>,
+}
+
+impl ToTokens for WrapImpl {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self.emit() {
+            Ok(output) => tokens.extend(output),
+            Err(err) => tokens.extend(err.to_error_tokens()),
+        }
+    }
+}
+
+impl WrapImpl {
+    #[allow(clippy::result_large_err)]
+    pub fn parse(input: Tokens, wrapper: Tokens) -> MacroResult<WrapImpl> {
+        let input = parse_tokens!(input)?;
+
+        Ok(WrapImpl::new(input).wrapper(wrapper))
+    }
+
+    pub fn new(input: Impl) -> WrapImpl {
+        WrapImpl {
+            input,
+            configure: vec![],
+            filters: vec![],
+            assertions: vec![],
+        }
+    }
+
+    /// Calls `wrapper` with each method's inner call, like [`WrapFn::wrapper`].
+    pub fn wrapper(self, wrapper: Tokens) -> Self {
+        self.configure(move |wrap_fn| wrap_fn.wrapper(wrapper.clone()))
+    }
+
+    /// Sets up the [`WrapFn`] of every wrapped method, e.g. with hooks, an
+    /// async mode or a return type mapping. Configurations run in the order
+    /// they were added.
+    pub fn configure(mut self, configure: impl Fn(WrapFn) -> WrapFn + 'static) -> Self {
+        self.configure.push(Box::new(configure));
+        self
+    }
+
+    /// Only wraps methods that satisfy `assertion`.
+    pub fn filter(mut self, assertion: impl AstAssertion<Function>) -> Self {
+        self.filters
+            .push(Box::new(move |method| assertion.assert(method).is_ok()));
+        self
+    }
+
+    /// Only wraps methods with one of the given names.
+    pub fn named(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let names: HashSet<String> = names.into_iter().map(|name| name.into()).collect();
+
+        self.filters.push(Box::new(move |method| {
+            names.contains(&method.name().to_string())
+        }));
+        self
+    }
+
+    /// Only wraps methods whose visibility satisfies `predicate`.
+    pub fn visibility(mut self, predicate: impl Fn(&syn::Visibility) -> bool + 'static) -> Self {
+        self.filters.push(Box::new(move |method| {
+            predicate(method.visibility().as_syn())
+        }));
+        self
+    }
+
+    /// Only wraps `pub` methods.
+    pub fn public(self) -> Self {
+        self.visibility(|vis| matches!(vis, syn::Visibility::Public(_)))
+    }
+
+    /// Requires every wrapped method to satisfy `assertion`.
+    pub fn assert(mut self, assertion: impl AstAssertion<Function>) -> Self {
+        self.assertions.push(assertion.assertion());
+        self
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn emit(&self) -> MacroResult<Tokens> {
+        let mut item = self.input.as_syn().clone();
+        let mut errors = Errors::new();
+
+        for impl_item in &mut item.items {
+            let method = match impl_item {
+                syn::ImplItem::Method(method) => Function::from(method.clone()),
+                _ => continue,
+            };
+
+            if !self.filters.iter().all(|filter| filter(&method)) {
+                continue;
+            }
+
+            if let Err(error) = assert_all(&self.assertions, &method) {
+                errors.push(error);
+                continue;
+            }
+
+            let wrapped = self
+                .configure
+                .iter()
+                .fold(WrapFn::new(method).in_impl(), |wrap_fn, configure| {
+                    configure(wrap_fn)
+                });
+
+            if let Some(tokens) = errors.handle(wrapped.emit()) {
+                *impl_item = syn::ImplItem::Verbatim(tokens);
+            }
+        }
+
+        errors.finish_with(item.to_token_stream())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TokenDiff;
+    use crate::tokens;
+
+    #[test]
+    fn configures_each_selected_method() {
+        let input = tokens!(
+            impl S {
+                fn get(&self) -> u8 {
+                    self.0
+                }
+
+                fn skipped(&self) {}
+            }
+        );
+
+        let wrapped = WrapImpl::parse(input, tokens!(wrap))
+            .unwrap()
+            .named(vec!["get"])
+            .configure(|wrap_fn| wrap_fn.before(tokens!(log();)));
+
+        let expected = tokens!(
+            impl S {
+                fn get(&self) -> u8 {
+                    log();
+
+                    #[allow(unused_mut)]
+                    let mut inner_get = move || -> u8 { self.0 };
+
+                    wrap(inner_get())
+                }
+
+                fn skipped(&self) {}
+            }
+        );
+
+        let diff = TokenDiff::new(&expected, &wrapped.emit().unwrap());
+        assert!(diff.is_same(), "{}", diff);
+    }
+}
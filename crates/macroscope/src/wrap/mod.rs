@@ -1 +1,2 @@
 pub mod function;
+pub mod impl_block;